@@ -0,0 +1,158 @@
+//! Extra balls, specials and replays, capped by the operator settings.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::credits::Credits;
+use crate::dmd::{Animation, Dmd};
+use crate::game::{Game, GameState, Points};
+use crate::lamp::{LampState, Lamps};
+use crate::operator::Settings;
+use crate::physics::StepSet;
+
+/// Lamp lit while the player up has an extra ball.
+const EXTRA_BALL_LAMP: &str = "extra_ball";
+
+/// What a special gives to the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SpecialAward {
+    Points(u64),
+    ExtraBall,
+    Credit,
+}
+
+/// Award given to the player up by the rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Event)]
+pub(crate) enum Award {
+    ExtraBall,
+    Special,
+}
+
+/// Awards of a player during a game.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlayerAwards {
+    /// Extra balls left to play
+    pub(crate) extra_balls: u32,
+    /// Extra balls awarded during the game
+    extra_balls_awarded: u32,
+    specials: u32,
+    /// Number of replay scores reached
+    replay_levels: usize,
+    replays: u32,
+}
+
+pub(crate) struct AwardPlugin;
+
+impl Plugin for AwardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Award>()
+            .add_systems(
+                FixedUpdate,
+                (give_awards, check_replays, light_extra_ball)
+                    .chain()
+                    .in_set(StepSet::Awards)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), turn_off_extra_ball);
+    }
+}
+
+fn announce(dmd: &mut Dmd, text: &str) {
+    dmd.play(Animation::Blink {
+        text: text.to_string(),
+        scale: 2,
+        period: 0.3,
+        duration: 2.,
+    });
+}
+
+fn extra_ball(awards: &mut PlayerAwards, settings: &Settings, dmd: &mut Dmd) {
+    if awards.extra_balls_awarded >= settings.max_extra_balls {
+        debug!("Extra ball limit reached");
+        return;
+    }
+    awards.extra_balls_awarded += 1;
+    awards.extra_balls += 1;
+    announce(dmd, "EXTRA BALL");
+}
+
+fn give_awards(
+    mut awards: EventReader<Award>,
+    mut game: ResMut<Game>,
+    mut credits: ResMut<Credits>,
+    mut points: EventWriter<Points>,
+    mut dmd: ResMut<Dmd>,
+    settings: Res<Settings>,
+) {
+    for award in awards.read() {
+        let player = &mut game.player_mut().awards;
+        match award {
+            Award::ExtraBall => extra_ball(player, &settings, &mut dmd),
+            Award::Special => {
+                if player.specials >= settings.max_specials {
+                    debug!("Special limit reached");
+                    continue;
+                }
+                player.specials += 1;
+                match settings.special_award {
+                    SpecialAward::Points(value) => {
+                        points.send(Points(value));
+                        announce(&mut dmd, "SPECIAL");
+                    }
+                    SpecialAward::ExtraBall => extra_ball(player, &settings, &mut dmd),
+                    SpecialAward::Credit => {
+                        credits.add(1, &settings);
+                        announce(&mut dmd, "SPECIAL");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Each replay score reached by a player awards a credit.
+fn check_replays(
+    mut game: ResMut<Game>,
+    mut credits: ResMut<Credits>,
+    mut dmd: ResMut<Dmd>,
+    settings: Res<Settings>,
+) {
+    if !game.is_changed() {
+        return;
+    }
+
+    let player = game.player();
+    let reached = settings
+        .replay_scores
+        .iter()
+        .filter(|score| **score > 0 && player.score >= **score)
+        .count();
+    if reached <= player.awards.replay_levels {
+        return;
+    }
+
+    let awards = &mut game.player_mut().awards;
+    for _ in awards.replay_levels..reached {
+        if awards.replays < settings.max_replays {
+            awards.replays += 1;
+            credits.add(1, &settings);
+            announce(&mut dmd, "REPLAY");
+        }
+    }
+    awards.replay_levels = reached;
+}
+
+fn light_extra_ball(game: Res<Game>, mut lamps: ResMut<Lamps>) {
+    let state = if game.player().awards.extra_balls > 0 {
+        LampState::On
+    } else {
+        LampState::Off
+    };
+    if lamps.get(EXTRA_BALL_LAMP) != state {
+        lamps.set(EXTRA_BALL_LAMP, state);
+    }
+}
+
+fn turn_off_extra_ball(mut lamps: ResMut<Lamps>) {
+    lamps.set(EXTRA_BALL_LAMP, LampState::Off);
+}
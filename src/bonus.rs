@@ -0,0 +1,158 @@
+//! End-of-ball bonus, counted on the display after the ball drained.
+//!
+//! The rules add to the bonus and advance its multiplier during the
+//! ball. Holding both flippers skips the count.
+
+use bevy::prelude::*;
+
+use crate::dmd::{format_number, Animation, Dmd};
+use crate::game::{BallEnded, GameState, NextBall, Points};
+use crate::physics::StepSet;
+use crate::replay::Button;
+use crate::tilt::Tilt;
+
+const MAX_MULTIPLIER: u32 = 10;
+/// Time in seconds to count the base bonus and the total.
+const COUNT_DURATION: f32 = 1.5;
+/// Time in seconds the multiplier is shown.
+const MULTIPLIER_DURATION: f32 = 1.;
+
+/// Bonus of the ball in play.
+#[derive(Clone, Debug, Resource)]
+pub(crate) struct Bonus {
+    pub(crate) value: u64,
+    /// Between 1 and [`MAX_MULTIPLIER`]
+    pub(crate) multiplier: u32,
+}
+
+impl Default for Bonus {
+    fn default() -> Self {
+        Self {
+            value: 0,
+            multiplier: 1,
+        }
+    }
+}
+
+impl Bonus {
+    pub(crate) fn add(&mut self, points: u64) {
+        self.value += points;
+    }
+
+    /// Advance the multiplier, `false` when it is already at the maximum.
+    pub(crate) fn advance_multiplier(&mut self) -> bool {
+        if self.multiplier < MAX_MULTIPLIER {
+            self.multiplier += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn total(&self) -> u64 {
+        self.value * u64::from(self.multiplier)
+    }
+}
+
+/// Bonus being counted.
+#[derive(Resource, Deref, DerefMut)]
+struct BonusCount(Timer);
+
+pub(crate) struct BonusPlugin;
+
+impl Plugin for BonusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bonus>()
+            .add_systems(OnEnter(GameState::Playing), reset_bonus)
+            .add_systems(
+                FixedUpdate,
+                (start_count, count)
+                    .chain()
+                    .in_set(StepSet::Bonus)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+fn reset_bonus(mut commands: Commands, mut bonus: ResMut<Bonus>) {
+    *bonus = Bonus::default();
+    commands.remove_resource::<BonusCount>();
+}
+
+fn start_count(
+    mut commands: Commands,
+    mut ended: EventReader<BallEnded>,
+    mut next_ball: EventWriter<NextBall>,
+    mut bonus: ResMut<Bonus>,
+    mut dmd: ResMut<Dmd>,
+    tilt: Res<Tilt>,
+) {
+    for _ in ended.read() {
+        if tilt.tilted || bonus.value == 0 {
+            *bonus = Bonus::default();
+            next_ball.send(NextBall);
+            continue;
+        }
+
+        debug!(
+            "Bonus {} x {} = {}",
+            bonus.value,
+            bonus.multiplier,
+            bonus.total()
+        );
+        let mut duration = COUNT_DURATION;
+        dmd.play(Animation::Count {
+            title: "BONUS".to_string(),
+            value: bonus.value,
+            duration: COUNT_DURATION,
+        });
+        if bonus.multiplier > 1 {
+            duration += MULTIPLIER_DURATION + COUNT_DURATION;
+            dmd.play(Animation::Blink {
+                text: format!("{} X {}", format_number(bonus.value), bonus.multiplier),
+                scale: 1,
+                // Shown during the whole duration
+                period: MULTIPLIER_DURATION * 2.,
+                duration: MULTIPLIER_DURATION,
+            });
+            dmd.play(Animation::Count {
+                title: "TOTAL BONUS".to_string(),
+                value: bonus.total(),
+                duration: COUNT_DURATION,
+            });
+        }
+        commands.insert_resource(BonusCount(Timer::from_seconds(duration, TimerMode::Once)));
+    }
+}
+
+/// The bonus is scored when the count ends, before the next ball.
+#[allow(clippy::too_many_arguments)]
+fn count(
+    mut commands: Commands,
+    time: Res<Time>,
+    buttons: Res<ButtonInput<Button>>,
+    count: Option<ResMut<BonusCount>>,
+    mut bonus: ResMut<Bonus>,
+    mut dmd: ResMut<Dmd>,
+    mut points: EventWriter<Points>,
+    mut next_ball: EventWriter<NextBall>,
+) {
+    let Some(mut count) = count else {
+        return;
+    };
+
+    let skip = buttons.pressed(Button::LeftFlipper) && buttons.pressed(Button::RightFlipper);
+    if skip {
+        dmd.stop();
+    }
+    if !skip && !count.tick(time.delta()).finished() {
+        return;
+    }
+
+    // The game adds the points before it reads the next ball, so they go
+    // to the player of the ball that ended
+    points.send(Points(bonus.total()));
+    *bonus = Bonus::default();
+    commands.remove_resource::<BonusCount>();
+    next_ball.send(NextBall);
+}
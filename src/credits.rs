@@ -0,0 +1,133 @@
+//! Coins and credits.
+//!
+//! The coin door switches are mapped to keys: 5, 6 and 7 are the coin
+//! slots. Coins are converted into credits by the pricing table of the
+//! operator settings.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::dmd::{Animation, Dmd};
+use crate::operator::Settings;
+use crate::physics::StepSet;
+use crate::replay::Button;
+use crate::storage;
+
+const STORAGE_KEY: &str = "credits";
+/// Keys of the coin slots, in the order of `Settings::coin_values`.
+pub(crate) const COIN_SLOTS: [KeyCode; 3] = [KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7];
+
+/// Number of coins needed for some credits, the best price applying
+/// first, e.g. 1 coin for 1 credit and 4 coins for 5 credits.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct Price {
+    pub(crate) coins: u32,
+    pub(crate) credits: u32,
+}
+
+/// Credits given for `coins` with `pricing`.
+fn credits_for(pricing: &[Price], coins: u32) -> u32 {
+    let mut prices: Vec<&Price> = pricing.iter().filter(|price| price.coins > 0).collect();
+    prices.sort_by_key(|price| std::cmp::Reverse(price.coins));
+
+    let mut remaining = coins;
+    let mut credits = 0;
+    for price in prices {
+        credits += remaining / price.coins * price.credits;
+        remaining %= price.coins;
+    }
+    credits
+}
+
+#[derive(Clone, Debug, Default, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Credits {
+    pub(crate) credits: u32,
+    /// Coins inserted since the last time the largest price was reached
+    coins: u32,
+}
+
+impl Credits {
+    /// Insert coins worth `value` and return the credits awarded.
+    fn insert_coins(&mut self, value: u32, settings: &Settings) -> u32 {
+        let before = credits_for(&settings.pricing, self.coins);
+        self.coins += value;
+        let awarded = credits_for(&settings.pricing, self.coins).saturating_sub(before);
+        if let Some(largest) = settings.pricing.iter().map(|price| price.coins).max() {
+            if largest > 0 {
+                self.coins %= largest;
+            }
+        }
+
+        let credits = (self.credits + awarded).min(settings.max_credits.max(self.credits));
+        let awarded = credits - self.credits;
+        self.credits = credits;
+        awarded
+    }
+
+    pub(crate) fn add(&mut self, credits: u32, settings: &Settings) {
+        self.credits = (self.credits + credits).min(settings.max_credits.max(self.credits));
+    }
+
+    /// Use a credit to start a game or add a player, always possible
+    /// on free play.
+    pub(crate) fn take(&mut self, settings: &Settings) -> bool {
+        if settings.free_play {
+            true
+        } else if self.credits > 0 {
+            self.credits -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Text shown on the displays.
+    pub(crate) fn text(&self, settings: &Settings) -> String {
+        if settings.free_play {
+            "FREE PLAY".to_string()
+        } else {
+            format!("CREDITS {}", self.credits)
+        }
+    }
+}
+
+pub(crate) struct CreditsPlugin;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load_or_default::<Credits>(STORAGE_KEY))
+            .add_systems(FixedUpdate, insert_coins.in_set(StepSet::Coins))
+            .add_systems(PostUpdate, save_credits.run_if(resource_changed::<Credits>));
+    }
+}
+
+fn insert_coins(
+    buttons: Res<ButtonInput<Button>>,
+    settings: Res<Settings>,
+    mut credits: ResMut<Credits>,
+    mut dmd: ResMut<Dmd>,
+) {
+    for (slot, value) in settings.coin_values.iter().enumerate() {
+        if !buttons.just_pressed(Button::Coin(slot)) {
+            continue;
+        }
+
+        let awarded = credits.insert_coins(*value, &settings);
+        debug!("Coin worth {value} inserted, {awarded} credits awarded");
+        if awarded > 0 {
+            dmd.play(Animation::Blink {
+                text: credits.text(&settings),
+                scale: 1,
+                period: 0.4,
+                duration: 1.2,
+            });
+        }
+    }
+}
+
+fn save_credits(credits: Res<Credits>) {
+    if let Err(error) = storage::save(STORAGE_KEY, credits.as_ref()) {
+        error!("Could not save credits: {error}");
+    }
+}
@@ -0,0 +1,94 @@
+//! Animations played on the display on top of the score.
+
+use bevy::prelude::IVec2;
+
+use super::frame::{text_height, text_width, Bitmap, Frame, DMD_HEIGHT, DMD_WIDTH};
+
+#[derive(Clone, Debug)]
+pub(crate) enum Animation {
+    /// Text entering from the right and leaving on the left.
+    Scroll {
+        text: String,
+        scale: i32,
+        /// Speed in dots per second
+        speed: f32,
+    },
+    /// Centered text blinking for `duration` seconds.
+    Blink {
+        text: String,
+        scale: i32,
+        /// Time in seconds of an on/off cycle
+        period: f32,
+        duration: f32,
+    },
+    /// Title above a number counting up from 0 to `value`, then
+    /// staying for the last fifth of `duration`.
+    Count {
+        title: String,
+        value: u64,
+        duration: f32,
+    },
+    /// Bitmap moving in a straight line during `duration` seconds.
+    Slide {
+        bitmap: Bitmap,
+        from: IVec2,
+        to: IVec2,
+        duration: f32,
+    },
+}
+
+impl Animation {
+    /// Duration in seconds of the animation.
+    pub(crate) fn duration(&self) -> f32 {
+        match self {
+            Animation::Scroll { text, scale, speed } => {
+                (DMD_WIDTH as i32 + text_width(text, *scale)) as f32 / speed
+            }
+            Animation::Blink { duration, .. }
+            | Animation::Count { duration, .. }
+            | Animation::Slide { duration, .. } => *duration,
+        }
+    }
+
+    /// Draw the animation as it is `elapsed` seconds after it started.
+    pub(crate) fn draw(&self, frame: &mut Frame, elapsed: f32) {
+        match self {
+            Animation::Scroll { text, scale, speed } => {
+                let x = DMD_WIDTH as i32 - (elapsed * speed) as i32;
+                let y = (DMD_HEIGHT as i32 - text_height(*scale)) / 2;
+                frame.text(x, y, text, *scale);
+            }
+            Animation::Blink {
+                text,
+                scale,
+                period,
+                ..
+            } => {
+                if elapsed % period < period / 2. {
+                    let y = (DMD_HEIGHT as i32 - text_height(*scale)) / 2;
+                    frame.text_centered(y, text, *scale);
+                }
+            }
+            Animation::Count {
+                title,
+                value,
+                duration,
+            } => {
+                let ratio = (elapsed / (duration * 0.8)).min(1.);
+                frame.text_centered(2, title, 1);
+                frame.number_centered(14, (*value as f64 * ratio as f64) as u64, 2);
+            }
+            Animation::Slide {
+                bitmap,
+                from,
+                to,
+                duration,
+            } => {
+                let position = from
+                    .as_vec2()
+                    .lerp(to.as_vec2(), (elapsed / duration).min(1.));
+                frame.bitmap(position.x as i32, position.y as i32, bitmap);
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+// Draw each texel of the display as a round dot.
+
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+@group(1) @binding(0) var<uniform> color: vec4<f32>;
+@group(1) @binding(1) var dots_texture: texture_2d<f32>;
+@group(1) @binding(2) var dots_sampler: sampler;
+
+// Brightness of an unlit dot, real displays show a faint grid.
+const OFF_BRIGHTNESS: f32 = 0.06;
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(dots_texture));
+    let position = in.uv * size;
+    let cell = floor(position);
+    let dist = length(position - cell - vec2<f32>(0.5));
+    let mask = 1.0 - smoothstep(0.35, 0.45, dist);
+
+    let brightness = textureSample(dots_texture, dots_sampler, (cell + vec2<f32>(0.5)) / size).r;
+    let intensity = max(brightness, OFF_BRIGHTNESS) * mask;
+
+    return vec4<f32>(color.rgb * intensity, 1.0);
+}
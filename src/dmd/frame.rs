@@ -0,0 +1,162 @@
+//! Dot buffer of the display and the drawing primitives.
+
+use super::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+/// Display width in dots.
+pub(crate) const DMD_WIDTH: usize = 128;
+/// Display height in dots.
+pub(crate) const DMD_HEIGHT: usize = 32;
+/// Brightness of a lit dot.
+pub(crate) const DOT_ON: u8 = u8::MAX;
+
+/// Brightness of every dot of the display, row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Frame {
+    dots: Vec<u8>,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            dots: vec![0; DMD_WIDTH * DMD_HEIGHT],
+        }
+    }
+}
+
+impl Frame {
+    pub(crate) fn dots(&self) -> &[u8] {
+        &self.dots
+    }
+
+    /// Set the brightness of a dot. Dots outside the display are ignored
+    /// so that elements can be drawn partially (e.g. when scrolling).
+    pub(crate) fn set(&mut self, x: i32, y: i32, brightness: u8) {
+        if (0..DMD_WIDTH as i32).contains(&x) && (0..DMD_HEIGHT as i32).contains(&y) {
+            self.dots[y as usize * DMD_WIDTH + x as usize] = brightness;
+        }
+    }
+
+    /// Write `text` with its top left corner at (`x`, `y`). Each dot of
+    /// the font is drawn as a `scale` x `scale` square.
+    pub(crate) fn text(&mut self, x: i32, y: i32, text: &str, scale: i32) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i as i32 * (GLYPH_WIDTH + 1) * scale;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                        continue;
+                    }
+                    for dx in 0..scale {
+                        for dy in 0..scale {
+                            self.set(
+                                left + column * scale + dx,
+                                y + row as i32 * scale + dy,
+                                DOT_ON,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write `text` horizontally centered.
+    pub(crate) fn text_centered(&mut self, y: i32, text: &str, scale: i32) {
+        let x = (DMD_WIDTH as i32 - text_width(text, scale)) / 2;
+        self.text(x, y, text, scale);
+    }
+
+    /// Write a number with thousands separators, horizontally centered.
+    pub(crate) fn number_centered(&mut self, y: i32, value: u64, scale: i32) {
+        self.text_centered(y, &format_number(value), scale);
+    }
+
+    /// Draw a bitmap with its top left corner at (`x`, `y`). Dots that
+    /// are off in the bitmap let the frame show through.
+    pub(crate) fn bitmap(&mut self, x: i32, y: i32, bitmap: &Bitmap) {
+        for row in 0..bitmap.height {
+            for column in 0..bitmap.width {
+                let brightness = bitmap.dots[row * bitmap.width + column];
+                if brightness > 0 {
+                    self.set(x + column as i32, y + row as i32, brightness);
+                }
+            }
+        }
+    }
+
+    /// Draw a two dots high gauge filled by `ratio` (between 0 and 1).
+    pub(crate) fn gauge(&mut self, x: i32, y: i32, width: i32, ratio: f32) {
+        let filled = (width as f32 * ratio.clamp(0., 1.)).round() as i32;
+        for i in 0..width {
+            let brightness = if i < filled { DOT_ON } else { DOT_ON / 4 };
+            self.set(x + i, y, brightness);
+            self.set(x + i, y + 1, brightness);
+        }
+    }
+}
+
+/// Width in dots of `text` once written.
+pub(crate) fn text_width(text: &str, scale: i32) -> i32 {
+    let count = text.chars().count() as i32;
+    if count == 0 {
+        0
+    } else {
+        (count * (GLYPH_WIDTH + 1) - 1) * scale
+    }
+}
+
+/// Height in dots of a line of text.
+pub(crate) fn text_height(scale: i32) -> i32 {
+    GLYPH_HEIGHT * scale
+}
+
+/// Format `value` with a comma every three digits.
+pub(crate) fn format_number(value: u64) -> String {
+    let digits = value.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+/// Small picture drawn on the display.
+#[derive(Clone, Debug)]
+pub(crate) struct Bitmap {
+    width: usize,
+    height: usize,
+    dots: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Build a bitmap from text rows where `#` is a lit dot, `+` a
+    /// half lit dot and anything else an off dot.
+    pub(crate) fn from_rows(rows: &[&str]) -> Self {
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = rows.len();
+        let mut dots = vec![0; width * height];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                dots[y * width + x] = match c {
+                    '#' => DOT_ON,
+                    '+' => DOT_ON / 2,
+                    _ => 0,
+                };
+            }
+        }
+
+        Self {
+            width,
+            height,
+            dots,
+        }
+    }
+}
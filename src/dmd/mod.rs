@@ -0,0 +1,304 @@
+//! Emulation of a 128x32 dot-matrix display (DMD).
+//!
+//! Systems draw into the [`Dmd`] frame, which is uploaded into a texture
+//! when it changes. The texture is rendered as an overlay by a shader
+//! giving the round dot look of the real displays.
+
+use std::collections::VecDeque;
+
+use bevy::asset::embedded_asset;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    AsBindGroup, Extent3d, ShaderRef, TextureDimension, TextureFormat,
+};
+use bevy::render::texture::ImageSampler;
+
+pub(crate) use animation::*;
+pub(crate) use frame::*;
+
+use crate::credits::Credits;
+use crate::game::{Game, GameState};
+use crate::highscore::{HighScores, InitialsEntry};
+use crate::operator::{Adjustment, OperatorMenu, Settings};
+use crate::rules::Rules;
+
+mod animation;
+mod font;
+mod frame;
+
+/// Size in pixels of a dot on screen.
+const DOT_SIZE: f32 = 5.;
+/// Color of a lit dot (classic plasma orange).
+const DOT_COLOR: Color = Color::srgb(1., 0.45, 0.);
+
+/// Content of the display.
+#[derive(Default, Resource)]
+pub(crate) struct Dmd {
+    pub(crate) frame: Frame,
+    animations: VecDeque<Animation>,
+    /// Time since the current animation started
+    elapsed: f32,
+    image: Handle<Image>,
+}
+
+impl Dmd {
+    /// Queue an animation. Animations are played one after the other
+    /// and hide the score while they play.
+    pub(crate) fn play(&mut self, animation: Animation) {
+        self.animations.push_back(animation);
+    }
+
+    /// Stop the animation playing and the queued ones.
+    pub(crate) fn stop(&mut self) {
+        self.animations.clear();
+        self.elapsed = 0.;
+    }
+
+    pub(crate) fn is_animating(&self) -> bool {
+        !self.animations.is_empty()
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+struct DmdMaterial {
+    #[uniform(0)]
+    color: LinearRgba,
+    #[texture(1)]
+    #[sampler(2)]
+    dots: Handle<Image>,
+}
+
+impl UiMaterial for DmdMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://pinball/dmd/dmd.wgsl".into()
+    }
+}
+
+pub(crate) struct DmdPlugin;
+
+impl Plugin for DmdPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "dmd.wgsl");
+
+        app.add_plugins(UiMaterialPlugin::<DmdMaterial>::default())
+            .init_resource::<Dmd>()
+            .add_systems(Startup, setup_dmd)
+            .add_systems(Update, (draw_score, animate, upload).chain());
+    }
+}
+
+fn setup_dmd(
+    mut commands: Commands,
+    mut dmd: ResMut<Dmd>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<DmdMaterial>>,
+) {
+    let mut image = Image::new(
+        Extent3d {
+            width: DMD_WIDTH as u32,
+            height: DMD_HEIGHT as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        dmd.frame.dots().to_vec(),
+        TextureFormat::R8Unorm,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    dmd.image = images.add(image);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                padding: UiRect::top(Val::Px(10.)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(MaterialNodeBundle {
+                style: Style {
+                    width: Val::Px(DMD_WIDTH as f32 * DOT_SIZE),
+                    height: Val::Px(DMD_HEIGHT as f32 * DOT_SIZE),
+                    ..default()
+                },
+                material: materials.add(DmdMaterial {
+                    color: DOT_COLOR.into(),
+                    dots: dmd.image.clone(),
+                }),
+                ..default()
+            });
+        });
+
+    dmd.play(Animation::Scroll {
+        text: "WELCOME TO PINBALL".into(),
+        scale: 2,
+        speed: 60.,
+    });
+    #[rustfmt::skip]
+    let ball = Bitmap::from_rows(&[
+        "..###..",
+        ".#####.",
+        "##+####",
+        "#######",
+        "#######",
+        ".#####.",
+        "..###..",
+    ]);
+    dmd.play(Animation::Slide {
+        bitmap: ball,
+        from: IVec2::new(-7, 12),
+        to: IVec2::new(DMD_WIDTH as i32, 12),
+        duration: 1.5,
+    });
+}
+
+/// Time in seconds each attract mode page is shown.
+const ATTRACT_PAGE_DURATION: f32 = 3.;
+
+/// Default content of the display, depending on the game state.
+#[allow(clippy::too_many_arguments)]
+fn draw_score(
+    mut dmd: ResMut<Dmd>,
+    game: Res<Game>,
+    rules: Res<Rules>,
+    high_scores: Res<HighScores>,
+    entry: Option<Res<InitialsEntry>>,
+    menu: Res<OperatorMenu>,
+    settings: Res<Settings>,
+    credits: Res<Credits>,
+    state: Res<State<GameState>>,
+    time: Res<Time>,
+) {
+    if dmd.is_animating() {
+        return;
+    }
+
+    let mut frame = Frame::default();
+    match state.get() {
+        GameState::Attract => {
+            let start = if settings.free_play || credits.credits > 0 {
+                "PRESS START"
+            } else {
+                "INSERT COIN"
+            };
+            let mut pages = vec![vec![
+                "PINBALL".to_string(),
+                start.to_string(),
+                credits.text(&settings),
+            ]];
+            let champion = high_scores
+                .grand_champion
+                .iter()
+                .map(|champion| ("GRAND CHAMPION".to_string(), champion));
+            let top = high_scores
+                .top
+                .iter()
+                .enumerate()
+                .map(|(rank, high_score)| (format!("HIGH SCORE {}", rank + 1), high_score));
+            for (title, high_score) in champion.chain(top) {
+                pages.push(vec![
+                    title,
+                    format!(
+                        "{} {}",
+                        high_score.initials,
+                        format_number(high_score.score)
+                    ),
+                    high_score.date(),
+                ]);
+            }
+
+            let page = (time.elapsed_seconds() / ATTRACT_PAGE_DURATION) as usize % pages.len();
+            let y = (DMD_HEIGHT as i32 - pages[page].len() as i32 * 10 + 3) / 2;
+            for (i, line) in pages[page].iter().enumerate() {
+                frame.text_centered(y + i as i32 * 10, line, 1);
+            }
+        }
+        GameState::Playing => {
+            let player = game.player();
+            frame.number_centered(2, player.score, 2);
+            let ball = if game.players.len() > 1 {
+                format!("P{} BALL {}", game.current + 1, player.ball)
+            } else {
+                format!("BALL {}", player.ball)
+            };
+            frame.text(2, 20, &ball, 1);
+            if let Some((mode, progress)) = rules.current() {
+                let mode = mode.to_uppercase();
+                frame.text(DMD_WIDTH as i32 - 2 - text_width(&mode, 1), 20, &mode, 1);
+                frame.gauge(2, 29, DMD_WIDTH as i32 - 4, progress);
+            }
+        }
+        GameState::HighScoreEntry => {
+            if let Some(entry) = entry {
+                let player = entry.players.front().map_or(0, |player| player + 1);
+                frame.text_centered(2, &format!("PLAYER {player} INITIALS"), 1);
+                let initials = entry.initials().replace(' ', "_");
+                frame.text_centered(14, &initials, 2);
+            }
+        }
+        GameState::Operator => {
+            frame.text_centered(2, "OPERATOR MENU", 1);
+            let adjustments = Adjustment::all(&settings);
+            if let Some(adjustment) = adjustments.get(menu.selected) {
+                frame.text_centered(12, &adjustment.label().to_uppercase(), 1);
+                frame.text_centered(22, &adjustment.value(&settings).to_uppercase(), 1);
+            }
+        }
+        GameState::GameOver => {
+            if let [player] = game.players.as_slice() {
+                frame.text_centered(4, "GAME OVER", 1);
+                frame.number_centered(17, player.score, 1);
+            } else {
+                for (i, player) in game.players.iter().enumerate() {
+                    let line = format!("P{} {}", i + 1, format_number(player.score));
+                    frame.text_centered(i as i32 * 8, &line, 1);
+                }
+            }
+        }
+    }
+    show(&mut dmd, frame);
+}
+
+fn animate(mut dmd: ResMut<Dmd>, time: Res<Time>) {
+    // Only a new frame needs to be uploaded
+    let state = dmd.bypass_change_detection();
+    state.elapsed += time.delta_seconds();
+    while let Some(animation) = state.animations.front() {
+        if state.elapsed < animation.duration() {
+            break;
+        }
+        state.elapsed -= animation.duration();
+        state.animations.pop_front();
+    }
+
+    let Some(animation) = state.animations.front() else {
+        state.elapsed = 0.;
+        return;
+    };
+    let mut frame = Frame::default();
+    animation.draw(&mut frame, state.elapsed);
+    show(&mut dmd, frame);
+}
+
+/// Replace the frame of the display, marking it changed only when it is
+/// different.
+fn show(dmd: &mut ResMut<Dmd>, frame: Frame) {
+    if dmd.frame != frame {
+        dmd.frame = frame;
+    }
+}
+
+fn upload(dmd: Res<Dmd>, mut images: ResMut<Assets<Image>>) {
+    if !dmd.is_changed() {
+        return;
+    }
+
+    if let Some(image) = images.get_mut(&dmd.image) {
+        image.data.copy_from_slice(dmd.frame.dots());
+    }
+}
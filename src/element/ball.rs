@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::PinballMaterial;
+use crate::{Ball, BALL_GROUP, BALL_RADIUS, TABLE_HEIGHT, TABLE_WIDTH, WALL_HEIGHT};
+
+/// Spawn a ball at the bottom of the launch lane.
+pub(crate) fn ball(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    table: Entity,
+) -> Entity {
+    let mesh = Mesh::from(Sphere {
+        radius: BALL_RADIUS,
+    });
+    let ball = commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(mesh),
+            // Steel, only its look: the ball has its own physics and makes the
+            // sound of what it hits
+            material: materials.add(PinballMaterial::Metal.render()),
+            ..default()
+        })
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::ball(BALL_RADIUS))
+        .insert(Velocity::default())
+        .insert(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
+        // Above the weight of the ball, only the hits sound
+        .insert(ContactForceEventThreshold(1.))
+        .insert(CollisionGroups::new(BALL_GROUP, Group::all() - BALL_GROUP))
+        .insert(TransformBundle::from(Transform::from_xyz(
+            TABLE_WIDTH / 2. - (BALL_RADIUS + 0.001),
+            -WALL_HEIGHT / 2. + BALL_RADIUS + 0.001,
+            TABLE_HEIGHT / 2. - (BALL_RADIUS + 0.001),
+        )))
+        .insert(Ccd::enabled())
+        .insert(Dominance::group(0))
+        .insert(Name::new("ball"))
+        .insert(Ball)
+        .id();
+    commands.entity(table).add_child(ball);
+
+    ball
+}
@@ -0,0 +1,32 @@
+//! Colliders made from meshes. Triangles are exact but make poor contacts,
+//! and let the ball go through on moving bodies: each element picks what
+//! suits its shape, or inserts primitives when they fit it better.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// How the collider of an element is made.
+#[derive(Clone, Debug)]
+pub(crate) enum ColliderStrategy {
+    /// Triangles of the mesh, for fixed elements only: contacts with
+    /// triangles are poor on moving bodies
+    TriMesh,
+    /// Convex hull of the mesh
+    ConvexHull,
+    /// Convex parts of a concave mesh
+    Decomposition,
+}
+
+impl ColliderStrategy {
+    /// Collider of `mesh`, `None` if it has no triangles with positions.
+    pub(crate) fn collider(self, mesh: &Mesh) -> Option<Collider> {
+        let shape = match self {
+            ColliderStrategy::TriMesh => ComputedColliderShape::TriMesh,
+            ColliderStrategy::ConvexHull => ComputedColliderShape::ConvexHull,
+            ColliderStrategy::Decomposition => {
+                ComputedColliderShape::ConvexDecomposition(VHACDParameters::default())
+            }
+        };
+        Collider::from_bevy_mesh(mesh, &shape)
+    }
+}
@@ -0,0 +1,57 @@
+//! What the elements are made of: how they bounce, how they look and how
+//! they sound when the ball hits them.
+
+use bevy::prelude::*;
+
+/// Material of an element. Its restitution and friction come from the
+/// `PhysicsConfig`, its look from `render`, its hit sound from `name`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Component)]
+pub(crate) enum PinballMaterial {
+    Metal,
+    Rubber,
+    Wood,
+    Plastic,
+    Glass,
+}
+
+impl PinballMaterial {
+    pub(crate) const ALL: [PinballMaterial; 5] = [
+        PinballMaterial::Metal,
+        PinballMaterial::Rubber,
+        PinballMaterial::Wood,
+        PinballMaterial::Plastic,
+        PinballMaterial::Glass,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            PinballMaterial::Metal => "metal",
+            PinballMaterial::Rubber => "rubber",
+            PinballMaterial::Wood => "wood",
+            PinballMaterial::Plastic => "plastic",
+            PinballMaterial::Glass => "glass",
+        }
+    }
+
+    /// Material to render the element with.
+    pub(crate) fn render(self) -> StandardMaterial {
+        let (base_color, metallic, perceptual_roughness) = match self {
+            PinballMaterial::Metal => (Color::srgb(0.8, 0.8, 0.82), 1., 0.25),
+            PinballMaterial::Rubber => (Color::srgb(0.9, 0.9, 0.88), 0., 0.9),
+            PinballMaterial::Wood => (Color::srgb(0.55, 0.4, 0.25), 0., 0.6),
+            PinballMaterial::Plastic => (Color::srgb(0.85, 0.2, 0.15), 0., 0.4),
+            PinballMaterial::Glass => (Color::NONE, 0., 0.1),
+        };
+        StandardMaterial {
+            base_color,
+            metallic,
+            perceptual_roughness,
+            alpha_mode: if base_color.alpha() < 1. {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            ..default()
+        }
+    }
+}
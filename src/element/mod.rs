@@ -1,5 +1,15 @@
+pub(crate) use ball::*;
+pub(crate) use collider::*;
 pub(crate) use flipper::*;
+pub(crate) use material::*;
+pub(crate) use model::*;
+pub(crate) use post::*;
 pub(crate) use table::*;
 
+mod ball;
+mod collider;
 mod flipper;
+mod material;
+mod model;
+mod post;
 mod table;
@@ -0,0 +1,240 @@
+//! Elements modeled by artists (toys, plastics, ramps...), placed on the
+//! table by `assets/table/models.ron` without any code.
+//!
+//! A model is a glTF scene. The meshes named `<anything>_collider` are its
+//! colliders and are hidden; it can instead collide with the convex hulls
+//! or the convex decomposition of its meshes, or not at all.
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::scene::SceneInstanceReady;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{ColliderStrategy, PinballMaterial, Playfield};
+use crate::{BALL_GROUP, TABLE_GROUP};
+
+const MODELS_PATH: &str = "table/models.ron";
+const COLLIDER_SUFFIX: &str = "_collider";
+
+/// What the ball collides with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub(crate) enum ModelCollider {
+    /// The meshes named `*_collider`
+    #[default]
+    Named,
+    /// Convex hulls of the meshes
+    ConvexHull,
+    /// Convex decomposition of the meshes, for models without simplified
+    /// colliders
+    Decomposition,
+    /// Nothing, the model is a decoration
+    None,
+}
+
+/// Model placed on the table.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Placement {
+    /// glTF file in the assets
+    path: String,
+    /// Position in the table, in meters, the playfield being at -0.02 high
+    #[serde(default)]
+    translation: [f32; 3],
+    /// Rotation around the normal of the playfield in degrees
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default = "default_scale")]
+    scale: f32,
+    #[serde(default)]
+    collider: ModelCollider,
+}
+
+fn default_scale() -> f32 {
+    1.
+}
+
+impl Placement {
+    fn transform(&self) -> Transform {
+        Transform::from_translation(Vec3::from(self.translation))
+            .with_rotation(Quat::from_rotation_y(self.rotation.to_radians()))
+            .with_scale(Vec3::splat(self.scale))
+    }
+}
+
+/// Models of the table.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct Models(Vec<Placement>);
+
+#[derive(Debug, Error)]
+pub(crate) enum ModelsLoaderError {
+    #[error("Could not read models: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse models: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub(crate) struct ModelsLoader;
+
+impl AssetLoader for ModelsLoader {
+    type Asset = Models;
+    type Settings = ();
+    type Error = ModelsLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(Models(ron::de::from_bytes(&bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["models.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct ModelList(Handle<Models>);
+
+/// Root of a model, parent of its scene.
+#[derive(Component)]
+pub(crate) struct Model {
+    path: String,
+    collider: ModelCollider,
+}
+
+/// Load the models of the table. Their scenes need a renderer, so they
+/// aren't in the headless simulation.
+pub(crate) struct ModelPlugin;
+
+impl Plugin for ModelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Models>()
+            .init_asset_loader::<ModelsLoader>()
+            .add_systems(Startup, load_models)
+            .add_systems(Update, (place_models, add_colliders));
+    }
+}
+
+fn load_models(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ModelList(asset_server.load(MODELS_PATH)));
+}
+
+/// Place the models once loaded, again when the list is reloaded.
+fn place_models(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Models>>,
+    asset_server: Res<AssetServer>,
+    list: Res<ModelList>,
+    models: Res<Assets<Models>>,
+    playfield: Res<Playfield>,
+    placed: Query<Entity, With<Model>>,
+) {
+    if !events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&list.0))
+    {
+        return;
+    }
+    let Some(Models(placements)) = models.get(&list.0) else {
+        return;
+    };
+
+    for entity in &placed {
+        commands.entity(entity).despawn_recursive();
+    }
+    for placement in placements {
+        debug!("Model {}", placement.path);
+        let model = commands
+            .spawn(SceneBundle {
+                scene: asset_server
+                    .load(GltfAssetLabel::Scene(0).from_asset(placement.path.clone())),
+                transform: placement.transform(),
+                ..default()
+            })
+            .insert(Name::new(placement.path.clone()))
+            .insert(Model {
+                path: placement.path.clone(),
+                collider: placement.collider,
+            })
+            .insert(RigidBody::Fixed)
+            .id();
+        commands.entity(playfield.0).add_child(model);
+    }
+}
+
+/// The mesh of `entity` or its node is named `*_collider`.
+fn is_collider(entity: Entity, names: &Query<&Name>, parents: &Query<&Parent>) -> bool {
+    let named = |entity| {
+        names.get(entity).is_ok_and(|name| {
+            // Meshes with several primitives end with `.<primitive>`
+            let name = name.as_str();
+            let name = name.rsplit_once('.').map_or(name, |(name, _)| name);
+            name.ends_with(COLLIDER_SUFFIX)
+        })
+    };
+    named(entity) || parents.get(entity).is_ok_and(|parent| named(parent.get()))
+}
+
+/// Add the colliders of a model once its scene is spawned.
+#[allow(clippy::too_many_arguments)]
+fn add_colliders(
+    mut commands: Commands,
+    mut ready: EventReader<SceneInstanceReady>,
+    meshes: Res<Assets<Mesh>>,
+    models: Query<&Model>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    parents: Query<&Parent>,
+    handles: Query<&Handle<Mesh>>,
+) {
+    for event in ready.read() {
+        let Ok(model) = models.get(event.parent) else {
+            continue;
+        };
+        // Meshes named as colliders are hidden even if they are not used
+        let strategy = match model.collider {
+            ModelCollider::Named => Some(ColliderStrategy::TriMesh),
+            ModelCollider::ConvexHull => Some(ColliderStrategy::ConvexHull),
+            ModelCollider::Decomposition => Some(ColliderStrategy::Decomposition),
+            ModelCollider::None => None,
+        };
+
+        let mut colliders = 0;
+        for entity in children.iter_descendants(event.parent) {
+            let Some(mesh) = handles.get(entity).ok().and_then(|mesh| meshes.get(mesh)) else {
+                continue;
+            };
+            let named = is_collider(entity, &names, &parents);
+            if named {
+                commands.entity(entity).insert(Visibility::Hidden);
+            }
+            let Some(strategy) = &strategy else {
+                continue;
+            };
+            if named != (model.collider == ModelCollider::Named) {
+                continue;
+            }
+            let Some(collider) = strategy.clone().collider(mesh) else {
+                warn!("Could not make a collider of a mesh of {}", model.path);
+                continue;
+            };
+            commands
+                .entity(entity)
+                .insert(collider)
+                .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+                .insert(PinballMaterial::Plastic);
+            colliders += 1;
+        }
+        if strategy.is_some() && colliders == 0 {
+            warn!("Model {} has no collider", model.path);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Posts, bare or wrapped in rubber, and the rubber bands stretched around
+//! them to shape the lanes.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::PinballMaterial;
+use crate::shapes::Band;
+use crate::{BALL_GROUP, BALL_RADIUS, RESOLUTION, TABLE_GROUP, WALL_HEIGHT};
+
+/// Radius of a bare post.
+const POST_RADIUS: f32 = 0.005;
+/// Thickness of the rubber of the bands and of the wrapped posts.
+const RUBBER_THICKNESS: f32 = 0.003;
+/// Height of a band, around the center of the ball.
+const BAND_HEIGHT: f32 = 0.01;
+
+/// Spawn a post at `position` in the table. A rubber post is wrapped in
+/// rubber, any other is bare.
+pub(crate) fn post(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    table: Entity,
+    name: &str,
+    position: Vec2,
+    material: PinballMaterial,
+) -> Entity {
+    let radius = match material {
+        PinballMaterial::Rubber => POST_RADIUS + RUBBER_THICKNESS,
+        _ => POST_RADIUS,
+    };
+    let mesh = Cylinder::new(radius, WALL_HEIGHT).into();
+    let collider = Collider::cylinder(WALL_HEIGHT / 2., radius);
+    let post = commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(mesh),
+            material: materials.add(material.render()),
+            transform: Transform::from_xyz(position.x, 0., position.y),
+            ..default()
+        })
+        .insert(Name::new(format!("post_{name}")))
+        .insert(RigidBody::Fixed)
+        .insert(collider)
+        .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(material)
+        .id();
+    commands.entity(table).add_child(post);
+
+    post
+}
+
+/// Spawn bare posts at `positions` in the table, in the order the band goes
+/// around them, and the band of rubber stretched around them.
+pub(crate) fn rubber_band(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    table: Entity,
+    name: &str,
+    positions: &[Vec2],
+) -> Entity {
+    for (i, position) in positions.iter().enumerate() {
+        post(
+            commands,
+            meshes,
+            materials,
+            table,
+            &format!("{name}_{i}"),
+            *position,
+            PinballMaterial::Metal,
+        );
+    }
+
+    let band = Band {
+        posts: positions
+            .iter()
+            .map(|position| (*position, POST_RADIUS))
+            .collect(),
+        thickness: RUBBER_THICKNESS,
+        height: BAND_HEIGHT,
+        resolution: RESOLUTION,
+    };
+    let collider = band.collider().unwrap();
+    let mesh = band.try_into().unwrap();
+    let band = commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(mesh),
+            material: materials.add(PinballMaterial::Rubber.render()),
+            // Where the ball on the floor hits it
+            transform: Transform::from_xyz(0., -WALL_HEIGHT / 2. + BALL_RADIUS, 0.),
+            ..default()
+        })
+        .insert(Name::new(format!("band_{name}")))
+        .insert(RigidBody::Fixed)
+        .insert(collider)
+        .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Rubber)
+        .id();
+    commands.entity(table).add_child(band);
+
+    band
+}
@@ -3,28 +3,43 @@ use std::f32::consts::PI;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use super::Side;
+use super::{post, rubber_band, ColliderStrategy, PinballMaterial, Side};
+use crate::game::Drain;
+use crate::kicker::{Kicker, Saucer};
+use crate::lamp::Lamp;
 use crate::shapes::{Ellipse, Flipper, Origin, Table};
+use crate::switch::Switch;
 use crate::{
-    BALL_GROUP, BALL_RADIUS, FLIPPERS_GROUP, FLIPPER_BIG, FLIPPER_SMALL, GUIDE_HEIGHT, RESOLUTION,
-    TABLE_GROUP, TABLE_HEIGHT, TABLE_INCLINATION, TABLE_WIDTH, WALL_HEIGHT,
+    BALL_GROUP, BALL_RADIUS, COLLIDER_RESOLUTION, FLIPPERS_GROUP, FLIPPER_BIG, FLIPPER_SMALL,
+    FLIPPER_SWING, GUIDE_HEIGHT, RESOLUTION, TABLE_GROUP, TABLE_HEIGHT, TABLE_INCLINATION,
+    TABLE_WIDTH, WALL_HEIGHT,
 };
 
+/// Width of the lanes on each side of the drain.
+const OUTLANE_WIDTH: f32 = 0.06;
+
+/// Table entity, parent of every element and of the balls.
+#[derive(Resource)]
+pub(crate) struct Playfield(pub(crate) Entity);
+
 fn table(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
 ) -> Entity {
-    let mesh = Mesh::from(Table::new(TABLE_HEIGHT, TABLE_WIDTH, WALL_HEIGHT));
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    let shape = Table::new(TABLE_HEIGHT, TABLE_WIDTH, WALL_HEIGHT);
+    let mesh = Mesh::from(shape);
+    let collider = shape.collider();
     commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+            material: materials.add(PinballMaterial::Wood.render()),
             transform: Transform::from_rotation(Quat::from_rotation_x(TABLE_INCLINATION)),
             ..default()
         })
+        .insert(Name::new("table"))
         .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Wood)
         .insert(RigidBody::Fixed)
         .insert(collider)
         .id()
@@ -43,23 +58,25 @@ fn linear_guide(
             Quat::from_rotation_y(-PI / 2.)
         };
         let mesh = Mesh::from(Rectangle::new(GUIDE_HEIGHT, WALL_HEIGHT));
-        let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+        let collider = ColliderStrategy::TriMesh.collider(&mesh).unwrap();
         let guide = commands
             .spawn(PbrBundle {
                 mesh: meshes.add(mesh),
-                material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+                material: materials.add(PinballMaterial::Metal.render()),
                 ..default()
             })
             .insert(
                 Transform::from_rotation(rotation).with_translation(Vec3::new(
-                    TABLE_WIDTH / 2. - (BALL_RADIUS + 0.05) * 2.,
+                    TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005) * 2.,
                     0.,
                     (TABLE_HEIGHT - GUIDE_HEIGHT) / 2.,
                 )),
             )
+            .insert(Name::new("launch_guide"))
             .insert(RigidBody::Fixed)
             .insert(collider)
             .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+            .insert(PinballMaterial::Metal)
             .id();
         commands.entity(table).add_child(guide);
     }
@@ -71,35 +88,36 @@ fn elliptic_guide(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     table: Entity,
 ) {
-    let mesh = Ellipse {
+    let ellipse = Ellipse {
         rectangle: false,
         center: Origin::MaxXMinZ,
         first_angle: 0.,
         second_angle: PI / 4.,
         resolution: RESOLUTION,
-        x: 2.5,
-        z: 0.9,
+        x: 0.25,
+        z: 0.09,
         thickness: WALL_HEIGHT,
-    }
-    .try_into()
-    .unwrap();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    };
+    let mesh = ellipse.try_into().unwrap();
+    let collider = ellipse.collider(COLLIDER_RESOLUTION).unwrap();
     let elliptic_guide = commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+            material: materials.add(PinballMaterial::Metal.render()),
             ..default()
         })
         .insert(
             Transform::from_rotation(Quat::from_rotation_x(PI)).with_translation(Vec3::new(
-                TABLE_WIDTH / 2. - (BALL_RADIUS + 0.05) * 2.,
+                TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005) * 2.,
                 0.,
                 TABLE_HEIGHT / 2. - GUIDE_HEIGHT,
             )),
         )
+        .insert(Name::new("launch_curve"))
         .insert(RigidBody::Fixed)
         .insert(collider)
         .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Metal)
         .id();
     commands.entity(table).add_child(elliptic_guide);
 }
@@ -110,23 +128,22 @@ fn top_ellipses(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     table: Entity,
 ) {
-    let mesh = Ellipse {
+    let ellipse = Ellipse {
         rectangle: true,
         center: Origin::MinXMaxZ,
         first_angle: 0.,
         second_angle: PI / 2.,
         resolution: RESOLUTION,
         x: TABLE_WIDTH / 2.,
-        z: 0.9,
+        z: 0.09,
         thickness: WALL_HEIGHT,
-    }
-    .try_into()
-    .unwrap();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    };
+    let mesh = ellipse.try_into().unwrap();
+    let collider = ellipse.collider(COLLIDER_RESOLUTION).unwrap();
     let up = commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+            material: materials.add(PinballMaterial::Metal.render()),
             ..default()
         })
         .insert(
@@ -136,30 +153,31 @@ fn top_ellipses(
                 -TABLE_HEIGHT / 2.,
             )),
         )
+        .insert(Name::new("top_left_ellipse"))
         .insert(RigidBody::Fixed)
         .insert(collider)
         .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Metal)
         .id();
     commands.entity(table).add_child(up);
 
     // Top right ellipse
-    let mesh = Ellipse {
+    let ellipse = Ellipse {
         rectangle: true,
         center: Origin::MinXMaxZ,
         first_angle: 0.,
         second_angle: PI / 2.,
         resolution: RESOLUTION,
         x: TABLE_WIDTH / 2.,
-        z: 0.9,
+        z: 0.09,
         thickness: WALL_HEIGHT,
-    }
-    .try_into()
-    .unwrap();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    };
+    let mesh = ellipse.try_into().unwrap();
+    let collider = ellipse.collider(COLLIDER_RESOLUTION).unwrap();
     let up = commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+            material: materials.add(PinballMaterial::Metal.render()),
             ..default()
         })
         .insert(
@@ -169,9 +187,11 @@ fn top_ellipses(
                 -TABLE_HEIGHT / 2.,
             )),
         )
+        .insert(Name::new("top_right_ellipse"))
         .insert(RigidBody::Fixed)
         .insert(collider)
         .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Metal)
         .id();
     commands.entity(table).add_child(up);
 }
@@ -188,72 +208,76 @@ fn middle_left_ellipse(
         first_angle: PI / 2.,
         second_angle: PI / 8.,
         resolution: RESOLUTION,
-        x: 0.8,
-        z: 0.3,
+        x: 0.08,
+        z: 0.03,
         thickness: WALL_HEIGHT,
     };
     // Because of -PI/2 rotation in transform
     // x "is z".
     let x = ellipse.real_z();
     let mesh = ellipse.try_into().unwrap();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    let collider = ellipse.collider(COLLIDER_RESOLUTION).unwrap();
     let ellipse =
         commands
             .spawn(PbrBundle {
                 mesh: meshes.add(mesh),
-                material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+                material: materials.add(PinballMaterial::Rubber.render()),
                 ..default()
             })
             .insert(
                 Transform::from_rotation(Quat::from_rotation_y(-PI / 2.))
                     .with_translation(Vec3::new(-TABLE_WIDTH / 2., 0., 0.)),
             )
+            .insert(Name::new("middle_left_ellipse"))
             .insert(RigidBody::Fixed)
             .insert(collider)
             .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+            .insert(PinballMaterial::Rubber)
             .id();
     commands.entity(table).add_child(ellipse);
 
     // Upper left flipper
     let angle = -PI / 10.;
-    let mesh = Flipper::new(
-        0.7,
+    let flipper = Flipper::new(
+        0.07,
         FLIPPER_SMALL,
         FLIPPER_BIG,
-        WALL_HEIGHT - 0.02,
+        WALL_HEIGHT - 0.002,
         RESOLUTION,
-    )
-    .into();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    );
+    let mesh = flipper.into();
+    let collider = flipper.collider();
     let position_in_table = Vec3::new(
-        -TABLE_WIDTH / 2. + FLIPPER_BIG + x - angle.cos() * FLIPPER_BIG + 0.02,
+        -TABLE_WIDTH / 2. + FLIPPER_BIG + x - angle.cos() * FLIPPER_BIG + 0.002,
         0.,
-        (PI / 2.).sin() * FLIPPER_BIG + 0.02,
+        (PI / 2.).sin() * FLIPPER_BIG + 0.002,
     );
 
     let rotation = RevoluteJointBuilder::new(Vec3::new(0., 1., 0.))
         .local_anchor1(position_in_table)
         .local_anchor2(Vec3::ZERO)
-        .limits([angle, angle + PI / 3.]);
+        .limits([angle, angle + FLIPPER_SWING]);
     let upper_left_flipper = commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::WHITE),
+            material: materials.add(PinballMaterial::Rubber.render()),
             transform: Transform::from_translation(position_in_table)
                 .with_rotation(Quat::from_rotation_y(angle)),
             ..Default::default()
         })
+        .insert(Name::new("upper_left_flipper"))
         .insert(Side::Left)
         .insert(RigidBody::Dynamic)
         .insert(ActiveEvents::COLLISION_EVENTS)
         .insert(collider)
         .insert(CollisionGroups::new(FLIPPERS_GROUP, BALL_GROUP))
-        .insert(Restitution::coefficient(0.3))
+        .insert(PinballMaterial::Rubber)
         .insert(ImpulseJoint::new(table, rotation))
         .insert(Damping {
             linear_damping: 0.,
             angular_damping: 5.,
         })
+        .insert(ReadMassProperties::default())
         .insert(Ccd::enabled())
         .id();
     commands.entity(table).add_child(upper_left_flipper);
@@ -271,32 +295,34 @@ fn middle_right_ellipse(
         first_angle: PI / 2.,
         second_angle: PI / 8.,
         resolution: RESOLUTION,
-        x: 0.8,
-        z: 0.3,
+        x: 0.08,
+        z: 0.03,
         thickness: WALL_HEIGHT,
     };
     // Because of -PI/2 rotation in transform
     // x "is z".
     let z = ellipse.real_x();
     let mesh = ellipse.try_into().unwrap();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    let collider = ellipse.collider(COLLIDER_RESOLUTION).unwrap();
     let ellipse = commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::srgb(0.4, 0.4, 0.4)),
+            material: materials.add(PinballMaterial::Rubber.render()),
             ..default()
         })
         .insert(
             Transform::from_rotation(Quat::from_rotation_z(PI) * Quat::from_rotation_y(-PI / 2.))
                 .with_translation(Vec3::new(
-                    TABLE_WIDTH / 2. - (BALL_RADIUS + 0.05) * 2.,
+                    TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005) * 2.,
                     0.,
                     (TABLE_HEIGHT - GUIDE_HEIGHT) / 2. - z,
                 )),
         )
+        .insert(Name::new("middle_right_ellipse"))
         .insert(RigidBody::Fixed)
         .insert(collider)
         .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Rubber)
         .id();
     commands.entity(table).add_child(ellipse);
 }
@@ -307,22 +333,223 @@ fn glass(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     table: Entity,
 ) {
-    let mesh = Cuboid::new(TABLE_WIDTH, 0.05, TABLE_HEIGHT).into();
-    let collider = Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+    let mesh = Cuboid::new(TABLE_WIDTH, 0.005, TABLE_HEIGHT).into();
+    let collider = Collider::cuboid(TABLE_WIDTH / 2., 0.0025, TABLE_HEIGHT / 2.);
     let glass = commands
         .spawn(PbrBundle {
             mesh: meshes.add(mesh),
-            material: materials.add(Color::NONE),
+            material: materials.add(PinballMaterial::Glass.render()),
             transform: Transform::from_translation(Vec3::new(0., WALL_HEIGHT / 2., 0.)),
             ..Default::default()
         })
+        .insert(Name::new("glass"))
         .insert(RigidBody::Fixed)
         .insert(collider)
         .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(PinballMaterial::Glass)
         .id();
     commands.entity(table).add_child(glass);
 }
 
+fn drain(commands: &mut Commands, table: Entity) {
+    // The drain spans the whole bottom of the table except the launch lane,
+    // split to tell the outlanes from the center
+    let left_x = -TABLE_WIDTH / 2.;
+    let right_x = TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005) * 2.;
+    for (name, min_x, max_x) in [
+        ("left_outlane", left_x, left_x + OUTLANE_WIDTH),
+        ("center", left_x + OUTLANE_WIDTH, right_x - OUTLANE_WIDTH),
+        ("right_outlane", right_x - OUTLANE_WIDTH, right_x),
+    ] {
+        let drain = commands
+            .spawn(TransformBundle::from(Transform::from_xyz(
+                (min_x + max_x) / 2.,
+                0.,
+                TABLE_HEIGHT / 2. - BALL_RADIUS,
+            )))
+            .insert(Collider::cuboid(
+                (max_x - min_x) / 2.,
+                WALL_HEIGHT / 2.,
+                BALL_RADIUS,
+            ))
+            .insert(Sensor)
+            .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+            .insert(Name::new(format!("drain_{name}")))
+            .insert(Drain(name.to_string()))
+            .id();
+        commands.entity(table).add_child(drain);
+    }
+}
+
+fn posts(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    table: Entity,
+) {
+    // Between the rollover lanes
+    post(
+        commands,
+        meshes,
+        materials,
+        table,
+        "lanes",
+        Vec2::new(0., -TABLE_HEIGHT / 2. + 0.15),
+        PinballMaterial::Rubber,
+    );
+
+    // Above the split of the drain, parting the outlanes from the center
+    let left_x = -TABLE_WIDTH / 2. + OUTLANE_WIDTH;
+    let right_x = TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005) * 2. - OUTLANE_WIDTH;
+    for (name, x) in [("left_outlane", left_x), ("right_outlane", right_x)] {
+        rubber_band(
+            commands,
+            meshes,
+            materials,
+            table,
+            name,
+            &[
+                Vec2::new(x, TABLE_HEIGHT / 2. - 0.2),
+                Vec2::new(x, TABLE_HEIGHT / 2. - 0.1),
+            ],
+        );
+    }
+}
+
+fn switches(commands: &mut Commands, table: Entity) {
+    let lane_x = TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005);
+    for (name, x, z, half_width) in [
+        // Top of the launch lane
+        (
+            "launch",
+            lane_x,
+            -TABLE_HEIGHT / 2. + 0.14,
+            BALL_RADIUS + 0.005,
+        ),
+        // Rollover lanes under the top ellipses
+        ("left_lane", -0.1, -TABLE_HEIGHT / 2. + 0.15, 0.02),
+        ("right_lane", 0.1, -TABLE_HEIGHT / 2. + 0.15, 0.02),
+    ] {
+        let switch = commands
+            .spawn(TransformBundle::from(Transform::from_xyz(x, 0., z)))
+            .insert(Collider::cuboid(half_width, WALL_HEIGHT / 2., 0.005))
+            .insert(Sensor)
+            .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+            .insert(Name::new(format!("switch_{name}")))
+            .insert(Switch(name.to_string()))
+            .id();
+        commands.entity(table).add_child(switch);
+    }
+}
+
+fn kickers(commands: &mut Commands, table: Entity) {
+    // Bottom of the launch lane, used to launch the ball automatically
+    let kicker = commands
+        .spawn(TransformBundle::from(Transform::from_xyz(
+            TABLE_WIDTH / 2. - (BALL_RADIUS + 0.005),
+            0.,
+            TABLE_HEIGHT / 2. - 0.015,
+        )))
+        .insert(Collider::cuboid(
+            BALL_RADIUS + 0.005,
+            WALL_HEIGHT / 2.,
+            0.015,
+        ))
+        .insert(Sensor)
+        .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(Name::new("kicker_launch"))
+        .insert(Kicker {
+            name: "launch".to_string(),
+            impulse: Vec3::new(0., 0., -0.24),
+        })
+        .id();
+    commands.entity(table).add_child(kicker);
+}
+
+fn saucers(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    table: Entity,
+) {
+    // Scoop on the right of the playfield, ejecting towards the left flipper
+    let scoop = commands
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            0.12, 0., -0.1,
+        )))
+        .insert(Collider::cylinder(WALL_HEIGHT / 2., 0.005))
+        .insert(Sensor)
+        .insert(CollisionGroups::new(TABLE_GROUP, BALL_GROUP))
+        .insert(Name::new("saucer_scoop"))
+        .insert(Saucer {
+            name: "scoop".to_string(),
+            hold: 1.5,
+            impulse: Vec3::new(-0.05, 0., 0.1),
+        })
+        .with_children(|parent| {
+            parent.spawn(PbrBundle {
+                mesh: meshes.add(Cylinder::new(BALL_RADIUS + 0.002, 0.001)),
+                material: materials.add(Color::srgb(0.05, 0.05, 0.05)),
+                transform: Transform::from_xyz(0., -WALL_HEIGHT / 2. + 0.0005, 0.),
+                ..default()
+            });
+        })
+        .id();
+    commands.entity(table).add_child(scoop);
+}
+
+fn lamps(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    table: Entity,
+) {
+    let mesh = meshes.add(Cylinder::new(0.008, 0.001));
+    for (name, x, z, color) in [
+        (
+            "left_lane",
+            -0.1,
+            -TABLE_HEIGHT / 2. + 0.18,
+            Color::srgb(1., 0.8, 0.),
+        ),
+        (
+            "right_lane",
+            0.1,
+            -TABLE_HEIGHT / 2. + 0.18,
+            Color::srgb(1., 0.8, 0.),
+        ),
+        ("mode", 0., 0., Color::srgb(1., 0., 0.)),
+        (
+            "shoot_again",
+            0.,
+            TABLE_HEIGHT / 2. - 0.06,
+            Color::srgb(1., 1., 1.),
+        ),
+        (
+            "extra_ball",
+            0.,
+            TABLE_HEIGHT / 2. - 0.09,
+            Color::srgb(1., 0.5, 0.),
+        ),
+    ] {
+        let lamp = commands
+            .spawn(PbrBundle {
+                mesh: mesh.clone(),
+                // Each lamp needs its own material to be lit independently
+                material: materials.add(color.darker(0.3)),
+                transform: Transform::from_xyz(x, -WALL_HEIGHT / 2. + 0.0005, z),
+                ..default()
+            })
+            .insert(Name::new(format!("lamp_{name}")))
+            .insert(Lamp {
+                name: name.to_string(),
+                color,
+            })
+            .id();
+        commands.entity(table).add_child(lamp);
+    }
+}
+
 pub(crate) fn setup_table(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -347,5 +574,19 @@ pub(crate) fn setup_table(
     // Glass on top
     glass(commands, meshes, materials, table);
 
+    // Drain at the bottom
+    drain(commands, table);
+
+    // Posts and rubber bands
+    posts(commands, meshes, materials, table);
+
+    // Switches, kickers, saucers and lamps used by the rules
+    switches(commands, table);
+    kickers(commands, table);
+    saucers(commands, meshes, materials, table);
+    lamps(commands, meshes, materials, table);
+
+    commands.insert_resource(Playfield(table));
+
     table
 }
@@ -0,0 +1,423 @@
+//! Export of the meshes of the table, to model decorations around them.
+//!
+//! `pinball --export <file>` writes the table as the game builds it, to
+//! glTF 2.0 when `file` ends with `.gltf`, with the hierarchy and the
+//! transforms of the elements and their buffer in a `.bin` next to it, or
+//! to OBJ when it ends with `.obj`, every element placed in the world.
+//! Elements without a mesh, like sensors, are left out.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::element::Playfield;
+use crate::shapes::{MeshElements, MeshError};
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+#[derive(Debug, Error)]
+enum ExportError {
+    #[error("Unknown format, the file must end with .gltf or .obj")]
+    Format,
+    #[error("Mesh of {name}: {error}")]
+    Mesh { name: String, error: MeshError },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Element of the table, with its mesh and color if it has any.
+struct Object {
+    name: String,
+    transform: Transform,
+    mesh: Option<(MeshElements, Color)>,
+    children: Vec<Object>,
+}
+
+pub(crate) fn run() {
+    let Some(path) = crate::arg("--export") else {
+        eprintln!("Usage: pinball --export <file.gltf|file.obj>");
+        std::process::exit(1);
+    };
+    let path = Path::new(&path);
+    let format = path.extension().and_then(|extension| extension.to_str());
+    let result = table().and_then(|table| match format {
+        Some("gltf") => write_gltf(&table, path),
+        Some("obj") => write_obj(&table, path),
+        _ => Err(ExportError::Format),
+    });
+    match result {
+        Ok(()) => println!("Table exported to {}", path.display()),
+        Err(error) => {
+            eprintln!("Could not export the table to {}: {error}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Build the table as in the game.
+fn table() -> Result<Object, ExportError> {
+    let mut world = World::new();
+    world.init_resource::<Assets<Mesh>>();
+    world.init_resource::<Assets<StandardMaterial>>();
+    world.run_system_once(crate::setup);
+    let table = world.resource::<Playfield>().0;
+    Ok(object(&world, table)?.expect("the table has a mesh"))
+}
+
+fn object(world: &World, entity: Entity) -> Result<Option<Object>, ExportError> {
+    let entity = world.entity(entity);
+    let name = entity
+        .get::<Name>()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("{}", entity.id()));
+
+    let mesh = match entity
+        .get::<Handle<Mesh>>()
+        .and_then(|mesh| world.resource::<Assets<Mesh>>().get(mesh))
+    {
+        Some(mesh) => {
+            let mesh = MeshElements::try_from(mesh).map_err(|error| ExportError::Mesh {
+                name: name.clone(),
+                error,
+            })?;
+            let color = entity
+                .get::<Handle<StandardMaterial>>()
+                .and_then(|material| world.resource::<Assets<StandardMaterial>>().get(material))
+                .map(|material| material.base_color)
+                .unwrap_or(Color::WHITE);
+            Some((mesh, color))
+        }
+        None => None,
+    };
+
+    let mut children = Vec::new();
+    for child in entity.get::<Children>().into_iter().flatten() {
+        children.extend(object(world, *child)?);
+    }
+
+    if mesh.is_none() && children.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Object {
+        name,
+        transform: entity.get::<Transform>().copied().unwrap_or_default(),
+        mesh,
+        children,
+    }))
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Gltf {
+    asset: Asset,
+    scene: usize,
+    scenes: Vec<Scene>,
+    nodes: Vec<Node>,
+    meshes: Vec<GltfMesh>,
+    materials: Vec<Material>,
+    accessors: Vec<Accessor>,
+    buffer_views: Vec<BufferView>,
+    buffers: Vec<Buffer>,
+}
+
+#[derive(Serialize)]
+struct Asset {
+    version: &'static str,
+    generator: &'static str,
+}
+
+impl Default for Asset {
+    fn default() -> Self {
+        Self {
+            version: "2.0",
+            generator: "pinball",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Scene {
+    nodes: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct Node {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mesh: Option<usize>,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct GltfMesh {
+    name: String,
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Serialize)]
+struct Primitive {
+    attributes: Attributes,
+    indices: usize,
+    material: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct Attributes {
+    position: usize,
+    normal: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Material {
+    name: String,
+    pbr_metallic_roughness: PbrMetallicRoughness,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alpha_mode: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PbrMetallicRoughness {
+    base_color_factor: [f32; 4],
+    metallic_factor: f32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: usize,
+    component_type: u32,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<[f32; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<[f32; 3]>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    byte_offset: usize,
+    byte_length: usize,
+    target: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Buffer {
+    uri: String,
+    byte_length: usize,
+}
+
+impl Gltf {
+    /// Add the node of `object` and of its children, returning its index.
+    fn add_node(&mut self, object: &Object, data: &mut Vec<u8>) -> usize {
+        let children = object
+            .children
+            .iter()
+            .map(|child| self.add_node(child, data))
+            .collect();
+        let mesh = object
+            .mesh
+            .as_ref()
+            .map(|(mesh, color)| self.add_mesh(&object.name, mesh, *color, data));
+        let Transform {
+            translation,
+            rotation,
+            scale,
+        } = object.transform;
+        self.nodes.push(Node {
+            name: object.name.clone(),
+            mesh,
+            translation: translation.to_array(),
+            rotation: rotation.to_array(),
+            scale: scale.to_array(),
+            children,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn add_mesh(
+        &mut self,
+        name: &str,
+        mesh: &MeshElements,
+        color: Color,
+        data: &mut Vec<u8>,
+    ) -> usize {
+        let (min, max) = mesh.vertices.iter().fold(
+            (Vec3::INFINITY, Vec3::NEG_INFINITY),
+            |(min, max), vertex| (min.min(Vec3::from(*vertex)), max.max(Vec3::from(*vertex))),
+        );
+        let position = self.add_accessor(
+            data,
+            mesh.vertices
+                .iter()
+                .flatten()
+                .flat_map(|value| value.to_le_bytes()),
+            Accessor {
+                buffer_view: 0,
+                component_type: FLOAT,
+                count: mesh.vertices.len(),
+                kind: "VEC3",
+                min: Some(min.to_array()),
+                max: Some(max.to_array()),
+            },
+            ARRAY_BUFFER,
+        );
+        let normal = self.add_accessor(
+            data,
+            mesh.normals
+                .iter()
+                .flatten()
+                .flat_map(|value| value.to_le_bytes()),
+            Accessor {
+                buffer_view: 0,
+                component_type: FLOAT,
+                count: mesh.normals.len(),
+                kind: "VEC3",
+                min: None,
+                max: None,
+            },
+            ARRAY_BUFFER,
+        );
+        let indices = self.add_accessor(
+            data,
+            mesh.indices.iter().flat_map(|index| index.to_le_bytes()),
+            Accessor {
+                buffer_view: 0,
+                component_type: UNSIGNED_INT,
+                count: mesh.indices.len(),
+                kind: "SCALAR",
+                min: None,
+                max: None,
+            },
+            ELEMENT_ARRAY_BUFFER,
+        );
+
+        let color = color.to_linear();
+        self.materials.push(Material {
+            name: name.to_string(),
+            pbr_metallic_roughness: PbrMetallicRoughness {
+                base_color_factor: color.to_f32_array(),
+                metallic_factor: 0.,
+            },
+            alpha_mode: (color.alpha < 1.).then_some("BLEND"),
+        });
+        self.meshes.push(GltfMesh {
+            name: name.to_string(),
+            primitives: vec![Primitive {
+                attributes: Attributes { position, normal },
+                indices,
+                material: self.materials.len() - 1,
+            }],
+        });
+        self.meshes.len() - 1
+    }
+
+    /// Append `bytes` to the buffer in a view of their own, read by
+    /// `accessor`.
+    fn add_accessor(
+        &mut self,
+        data: &mut Vec<u8>,
+        bytes: impl Iterator<Item = u8>,
+        accessor: Accessor,
+        target: u32,
+    ) -> usize {
+        let byte_offset = data.len();
+        data.extend(bytes);
+        self.buffer_views.push(BufferView {
+            buffer: 0,
+            byte_offset,
+            byte_length: data.len() - byte_offset,
+            target,
+        });
+        self.accessors.push(Accessor {
+            buffer_view: self.buffer_views.len() - 1,
+            ..accessor
+        });
+        self.accessors.len() - 1
+    }
+}
+
+fn write_gltf(table: &Object, path: &Path) -> Result<(), ExportError> {
+    let mut gltf = Gltf::default();
+    let mut data = Vec::new();
+    let root = gltf.add_node(table, &mut data);
+    gltf.scenes.push(Scene { nodes: vec![root] });
+
+    let bin = path.with_extension("bin");
+    gltf.buffers.push(Buffer {
+        uri: bin
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        byte_length: data.len(),
+    });
+    std::fs::write(&bin, data)?;
+    std::fs::write(path, serde_json::to_string_pretty(&gltf)?)?;
+    Ok(())
+}
+
+fn write_obj(table: &Object, path: &Path) -> Result<(), ExportError> {
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "# Pinball table")?;
+    let mut vertices = 0;
+    write_object(&mut file, table, Affine3A::IDENTITY, &mut vertices)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Write `object` and its children placed in the world, `vertices` being
+/// the number of vertices already written.
+fn write_object(
+    file: &mut impl Write,
+    object: &Object,
+    parent: Affine3A,
+    vertices: &mut u32,
+) -> Result<(), ExportError> {
+    let transform = parent * object.transform.compute_affine();
+    if let Some((mesh, _)) = &object.mesh {
+        writeln!(file, "o {}", object.name)?;
+        for vertex in &mesh.vertices {
+            let [x, y, z] = transform.transform_point3(Vec3::from(*vertex)).to_array();
+            writeln!(file, "v {x} {y} {z}")?;
+        }
+        for normal in &mesh.normals {
+            // The elements are not scaled, the normals only turn
+            let [x, y, z] = transform
+                .transform_vector3(Vec3::from(*normal))
+                .normalize()
+                .to_array();
+            writeln!(file, "vn {x} {y} {z}")?;
+        }
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|corner| triangle[corner] + *vertices + 1);
+            writeln!(file, "f {a}//{a} {b}//{b} {c}//{c}")?;
+        }
+        *vertices += mesh.vertices.len() as u32;
+    }
+    for child in &object.children {
+        write_object(file, child, transform, vertices)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,402 @@
+//! Game progress shared by the displays and the rules.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::awards::PlayerAwards;
+use crate::credits::Credits;
+use crate::dmd::{Animation, Dmd};
+use crate::element::{self, Playfield};
+use crate::operator::Settings;
+use crate::physics::StepSet;
+use crate::replay::Button;
+use crate::tilt::Tilt;
+use crate::Ball;
+
+/// Maximum number of players in a game.
+pub(crate) const MAX_PLAYERS: usize = 4;
+/// Time in seconds the game over screen is shown before
+/// going back to attract mode.
+const GAME_OVER_DURATION: f32 = 5.;
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) enum GameState {
+    /// Waiting for a player to press start
+    #[default]
+    Attract,
+    Playing,
+    /// The player enters their initials after a high score
+    HighScoreEntry,
+    GameOver,
+    /// Operator menu
+    Operator,
+}
+
+/// Score and ball of a player.
+#[derive(Clone, Debug)]
+pub(crate) struct Player {
+    pub(crate) score: u64,
+    /// Ball being played, or to play next, starting at 1
+    pub(crate) ball: u32,
+    pub(crate) awards: PlayerAwards,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            ball: 1,
+            awards: PlayerAwards::default(),
+        }
+    }
+}
+
+/// Players of the game being played.
+#[derive(Clone, Debug, Resource)]
+pub(crate) struct Game {
+    pub(crate) players: Vec<Player>,
+    /// Index of the player up
+    pub(crate) current: usize,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self {
+            players: vec![Player::default()],
+            current: 0,
+        }
+    }
+}
+
+impl Game {
+    /// Player up.
+    pub(crate) fn player(&self) -> &Player {
+        &self.players[self.current]
+    }
+
+    pub(crate) fn player_mut(&mut self) -> &mut Player {
+        &mut self.players[self.current]
+    }
+
+    /// Players can be added during the first ball.
+    fn can_add_player(&self) -> bool {
+        self.players.len() < MAX_PLAYERS && self.player().ball == 1
+    }
+
+    fn add_player(&mut self) {
+        self.players.push(Player::default());
+    }
+
+    /// Give the table to the next player, `false` if the game is over.
+    fn next_player(&mut self, balls_per_game: u32) -> bool {
+        if self.current + 1 == self.players.len() && self.player().ball >= balls_per_game {
+            return false;
+        }
+
+        self.player_mut().ball += 1;
+        self.current = (self.current + 1) % self.players.len();
+        true
+    }
+}
+
+/// Points scored by the player.
+#[derive(Clone, Copy, Debug, Event)]
+pub(crate) struct Points(pub(crate) u64);
+
+/// Sensor at the bottom of the table that ends the ball in play.
+#[derive(Component)]
+pub(crate) struct Drain(pub(crate) String);
+
+/// Sent when the ball in play fell into a drain.
+#[derive(Event)]
+pub(crate) struct BallDrained {
+    /// Name of the drain
+    pub(crate) drain: String,
+}
+
+/// Sent when the ball in play drained and was not saved. The
+/// end-of-ball bonus is counted before [`NextBall`].
+#[derive(Event)]
+pub(crate) struct BallEnded;
+
+/// Sent when the end-of-ball sequence is over.
+#[derive(Event)]
+pub(crate) struct NextBall;
+
+/// Sent when the table is given to another player.
+#[derive(Event)]
+pub(crate) struct PlayerUp {
+    pub(crate) previous: usize,
+    pub(crate) current: usize,
+}
+
+/// Ask for a new ball in the launch lane.
+#[derive(Event)]
+pub(crate) struct ServeBall;
+
+/// While the timer runs, a drained ball is given back to the player.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct BallSave(Option<Timer>);
+
+impl BallSave {
+    pub(crate) fn start(&mut self, seconds: f32) {
+        self.0 = Some(Timer::from_seconds(seconds, TimerMode::Once));
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.0.as_ref().is_some_and(|timer| !timer.finished())
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct GameOverTimer(Timer);
+
+pub(crate) struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .init_resource::<Game>()
+            .init_resource::<BallSave>()
+            .add_event::<BallDrained>()
+            .add_event::<BallEnded>()
+            .add_event::<NextBall>()
+            .add_event::<PlayerUp>()
+            .add_event::<Points>()
+            .add_event::<ServeBall>()
+            .add_systems(
+                FixedUpdate,
+                start_game
+                    .in_set(StepSet::Start)
+                    .run_if(in_state(GameState::Attract).or_else(in_state(GameState::GameOver))),
+            )
+            .add_systems(OnEnter(GameState::Playing), first_ball)
+            .add_systems(
+                FixedUpdate,
+                (
+                    add_player,
+                    add_points,
+                    tick_ball_save,
+                    drain,
+                    end_ball,
+                    next_ball,
+                    serve_ball,
+                )
+                    .chain()
+                    .in_set(StepSet::Game)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), start_game_over_timer)
+            .add_systems(
+                Update,
+                back_to_attract.run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+fn start_game(
+    buttons: Res<ButtonInput<Button>>,
+    settings: Res<Settings>,
+    mut credits: ResMut<Credits>,
+    mut game: ResMut<Game>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut dmd: ResMut<Dmd>,
+) {
+    if !buttons.just_pressed(Button::Start) {
+        return;
+    }
+
+    if credits.take(&settings) {
+        *game = Game::default();
+        next_state.set(GameState::Playing);
+    } else {
+        dmd.play(insert_coin());
+    }
+}
+
+fn insert_coin() -> Animation {
+    Animation::Blink {
+        text: "INSERT COIN".to_string(),
+        scale: 2,
+        period: 0.4,
+        duration: 1.6,
+    }
+}
+
+/// Start adds a player during the first ball, using a credit.
+fn add_player(
+    buttons: Res<ButtonInput<Button>>,
+    settings: Res<Settings>,
+    mut credits: ResMut<Credits>,
+    mut game: ResMut<Game>,
+    mut dmd: ResMut<Dmd>,
+) {
+    if !buttons.just_pressed(Button::Start) || !game.can_add_player() {
+        return;
+    }
+    if !credits.take(&settings) {
+        dmd.play(insert_coin());
+        return;
+    }
+
+    game.add_player();
+    debug!("Player {} added", game.players.len());
+    dmd.play(Animation::Blink {
+        text: format!("PLAYER {}", game.players.len()),
+        scale: 2,
+        period: 0.3,
+        duration: 1.,
+    });
+}
+
+fn first_ball(mut ball_save: ResMut<BallSave>, mut serve: EventWriter<ServeBall>) {
+    *ball_save = BallSave::default();
+    serve.send(ServeBall);
+}
+
+fn serve_ball(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut serve: EventReader<ServeBall>,
+    playfield: Res<Playfield>,
+) {
+    for _ in serve.read() {
+        element::ball(&mut commands, &mut meshes, &mut materials, playfield.0);
+    }
+}
+
+/// A tilted ball does not score.
+fn add_points(mut points: EventReader<Points>, mut game: ResMut<Game>, tilt: Res<Tilt>) {
+    for Points(value) in points.read() {
+        if !tilt.tilted {
+            game.player_mut().score += value;
+        }
+    }
+}
+
+fn drain(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut drained: EventWriter<BallDrained>,
+    drains: Query<&Drain>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(first, second, _) = *event else {
+            continue;
+        };
+        let (ball, drain) = match (drains.get(first), drains.get(second)) {
+            (Ok(drain), _) if balls.contains(second) => (second, drain),
+            (_, Ok(drain)) if balls.contains(first) => (first, drain),
+            _ => continue,
+        };
+
+        commands.entity(ball).despawn_recursive();
+        drained.send(BallDrained {
+            drain: drain.0.clone(),
+        });
+    }
+}
+
+fn tick_ball_save(time: Res<Time>, mut ball_save: ResMut<BallSave>) {
+    if let Some(timer) = ball_save.0.as_mut() {
+        timer.tick(time.delta());
+    }
+}
+
+/// A drained ball is given back during the ball save, unless tilted.
+fn end_ball(
+    mut drained: EventReader<BallDrained>,
+    mut serve: EventWriter<ServeBall>,
+    mut ended: EventWriter<BallEnded>,
+    mut dmd: ResMut<Dmd>,
+    game: Res<Game>,
+    ball_save: Res<BallSave>,
+    tilt: Res<Tilt>,
+) {
+    for _ in drained.read() {
+        if ball_save.is_active() && !tilt.tilted {
+            debug!("Ball {} saved", game.player().ball);
+            dmd.play(Animation::Blink {
+                text: "BALL SAVED".to_string(),
+                scale: 2,
+                period: 0.4,
+                duration: 2.,
+            });
+            serve.send(ServeBall);
+        } else {
+            ended.send(BallEnded);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn next_ball(
+    mut next_ball: EventReader<NextBall>,
+    mut serve: EventWriter<ServeBall>,
+    mut player_up: EventWriter<PlayerUp>,
+    mut game: ResMut<Game>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut dmd: ResMut<Dmd>,
+    mut ball_save: ResMut<BallSave>,
+    settings: Res<Settings>,
+) {
+    for _ in next_ball.read() {
+        debug!(
+            "Ball {} of player {} ended",
+            game.player().ball,
+            game.current + 1
+        );
+        if game.player().awards.extra_balls > 0 {
+            game.player_mut().awards.extra_balls -= 1;
+            dmd.play(Animation::Blink {
+                text: "SHOOT AGAIN".to_string(),
+                scale: 2,
+                period: 0.4,
+                duration: 2.,
+            });
+            serve.send(ServeBall);
+            continue;
+        }
+
+        let previous = game.current;
+        if !game.next_player(settings.balls_per_game) {
+            next_state.set(GameState::GameOver);
+            continue;
+        }
+
+        if game.current != previous {
+            // The ball save of the previous player does not carry over
+            *ball_save = BallSave::default();
+            dmd.play(Animation::Blink {
+                text: format!("PLAYER {}", game.current + 1),
+                scale: 2,
+                period: 0.4,
+                duration: 1.5,
+            });
+            player_up.send(PlayerUp {
+                previous,
+                current: game.current,
+            });
+        }
+        serve.send(ServeBall);
+    }
+}
+
+fn start_game_over_timer(mut commands: Commands) {
+    commands.insert_resource(GameOverTimer(Timer::from_seconds(
+        GAME_OVER_DURATION,
+        TimerMode::Once,
+    )));
+}
+
+fn back_to_attract(
+    time: Res<Time>,
+    mut timer: ResMut<GameOverTimer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if timer.tick(time.delta()).just_finished() {
+        next_state.set(GameState::Attract);
+    }
+}
@@ -0,0 +1,163 @@
+//! Simulation without window, GPU or sound, running as fast as possible.
+//!
+//! `pinball --headless` plays a game for `--seconds <n>` simulated
+//! seconds (60 by default) and prints the scores and the drains. The
+//! inputs are the ones of `--replay [file]`, of `--inputs <file>`, or a
+//! launch. An inputs file lists the buttons held from a time in seconds
+//! since the start of the game, in RON:
+//!
+//! ```ron
+//! [(0.5, [Plunger]), (0.55, []), (2.0, [LeftFlipper]), (2.2, [])]
+//! ```
+//!
+//! The rules draw their random numbers from `--seed <n>` (0 by default).
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
+
+use crate::dmd::Dmd;
+use crate::game::{BallDrained, Game, GameState};
+use crate::highscore::HighScores;
+use crate::operator::Settings;
+use crate::replay::{self, Button, Recording, Session};
+use crate::sound::{Callout, PlaySound};
+use crate::{arg, storage};
+
+const DEFAULT_SECONDS: f64 = 60.;
+
+fn inputs(seed: u64) -> Recording {
+    if let Some(recording) = replay::recording_from_args() {
+        return recording;
+    }
+
+    let inputs = match arg("--inputs") {
+        Some(path) => {
+            let inputs = std::fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|content| ron::from_str(&content).map_err(|error| error.to_string()));
+            match inputs {
+                Ok(inputs) => inputs,
+                Err(error) => {
+                    error!("Could not load the inputs {path}: {error}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => vec![(0.5, vec![Button::Plunger]), (0.55, Vec::new())],
+    };
+    Recording::scripted(seed, inputs)
+}
+
+/// What happened during the simulation.
+#[derive(Debug, Default, Resource)]
+struct Results {
+    drains: BTreeMap<String, u32>,
+}
+
+#[derive(Resource)]
+struct Simulation {
+    seconds: f64,
+    started: Instant,
+}
+
+pub(crate) fn run() {
+    let seconds = arg("--seconds")
+        .and_then(|seconds| seconds.parse().ok())
+        .unwrap_or(DEFAULT_SECONDS);
+    let seed = arg("--seed")
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_default();
+
+    let mut recording = inputs(seed);
+    recording.extend_to(seconds);
+
+    let mut app = App::new();
+    app.add_plugins(LogPlugin {
+        filter: "warn,pinball=info".into(),
+        ..default()
+    });
+    add_simulation(&mut app, recording);
+    app.init_resource::<Results>()
+        .insert_resource(Simulation {
+            seconds,
+            started: Instant::now(),
+        })
+        .add_systems(Update, count_drains)
+        .add_systems(Last, end_simulation)
+        .run();
+}
+
+/// Table and rules replaying `recording`, one physics step an update.
+pub(crate) fn add_simulation(app: &mut App, recording: Recording) {
+    app.add_plugins((
+        MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)),
+        TransformPlugin,
+        HierarchyPlugin,
+        bevy::input::InputPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+        StatesPlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<StandardMaterial>();
+
+    // The simulation starts from the defaults, and nothing of it is kept
+    storage::set_read_only(true);
+
+    // Resources of the displays, menus and sounds left out
+    app.init_resource::<Dmd>()
+        .init_resource::<HighScores>()
+        .init_resource::<Settings>()
+        .add_event::<PlaySound>()
+        .add_event::<Callout>();
+
+    crate::add_table(app);
+    app.add_plugins(replay::ReplayPlugin {
+        replay: Some(recording),
+    });
+}
+
+fn count_drains(mut results: ResMut<Results>, mut drained: EventReader<BallDrained>) {
+    for event in drained.read() {
+        *results.drains.entry(event.drain.clone()).or_default() += 1;
+    }
+}
+
+fn end_simulation(
+    time: Res<Time<Virtual>>,
+    simulation: Res<Simulation>,
+    session: Res<Session>,
+    results: Res<Results>,
+    game: Res<Game>,
+    state: Res<State<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if time.elapsed_seconds_f64() < simulation.seconds && !session.finished() {
+        return;
+    }
+
+    println!(
+        "Simulated {:.1} s in {:.1} s",
+        time.elapsed_seconds_f64(),
+        simulation.started.elapsed().as_secs_f64()
+    );
+    println!("State: {:?}", state.get());
+    for (index, player) in game.players.iter().enumerate() {
+        println!(
+            "Player {}: {} points, ball {}",
+            index + 1,
+            player.score,
+            player.ball
+        );
+    }
+    for (drain, count) in &results.drains {
+        println!("Drain {drain}: {count}");
+    }
+    exit.send(AppExit::Success);
+}
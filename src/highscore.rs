@@ -0,0 +1,339 @@
+//! High score table, saved between sessions, and initials entry
+//! at the end of the game.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::utils::SystemTime;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, GameState};
+use crate::physics::StepSet;
+use crate::replay::Button;
+use crate::storage;
+
+/// Number of scores in the list, grand champion excluded.
+const TOP_SCORES: usize = 10;
+const INITIALS: usize = 3;
+/// Characters available for the initials.
+const CHARACTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ ";
+const STORAGE_KEY: &str = "highscores";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HighScore {
+    pub(crate) initials: String,
+    pub(crate) score: u64,
+    /// Seconds since the Unix epoch
+    pub(crate) date: u64,
+}
+
+impl HighScore {
+    /// Date as `YYYY-MM-DD`.
+    pub(crate) fn date(&self) -> String {
+        // Civil date from days, see http://howardhinnant.github.io/date_algorithms.html
+        let days = (self.date / 86_400) as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+#[derive(Clone, Debug, Default, Resource, Serialize, Deserialize)]
+pub(crate) struct HighScores {
+    pub(crate) grand_champion: Option<HighScore>,
+    /// Best scores after the grand champion, highest first
+    pub(crate) top: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Whether `score` enters the table.
+    pub(crate) fn qualifies(&self, score: u64) -> bool {
+        if score == 0 {
+            return false;
+        }
+        match &self.grand_champion {
+            None => true,
+            Some(champion) if score > champion.score => true,
+            Some(_) => {
+                self.top.len() < TOP_SCORES
+                    || self.top.last().is_some_and(|last| score > last.score)
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, entry: HighScore) {
+        let entry = match self.grand_champion.take() {
+            Some(champion) if entry.score > champion.score => {
+                self.grand_champion = Some(entry);
+                champion
+            }
+            None => {
+                self.grand_champion = Some(entry);
+                return;
+            }
+            champion => {
+                self.grand_champion = champion;
+                entry
+            }
+        };
+
+        let position = self
+            .top
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.top.len());
+        self.top.insert(position, entry);
+        self.top.truncate(TOP_SCORES);
+    }
+}
+
+/// Initials being entered by the players with a high score.
+#[derive(Debug, Resource)]
+pub(crate) struct InitialsEntry {
+    /// Index of the players left to enter their initials, the one
+    /// entering them first
+    pub(crate) players: VecDeque<usize>,
+    /// Index in [`CHARACTERS`] of each initial
+    characters: [usize; INITIALS],
+    /// Initial being edited
+    pub(crate) position: usize,
+}
+
+impl InitialsEntry {
+    fn new(players: VecDeque<usize>) -> Self {
+        Self {
+            players,
+            characters: [0; INITIALS],
+            position: 0,
+        }
+    }
+
+    pub(crate) fn initials(&self) -> String {
+        self.characters
+            .iter()
+            .map(|i| CHARACTERS[*i] as char)
+            .collect()
+    }
+}
+
+#[derive(Component)]
+struct InitialsScreen;
+
+#[derive(Component)]
+struct InitialsTitle;
+
+#[derive(Component)]
+struct InitialsText;
+
+pub(crate) struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load_or_default::<HighScores>(STORAGE_KEY))
+            .add_systems(Startup, setup_initials_screen)
+            .add_systems(
+                OnTransition {
+                    exited: GameState::Playing,
+                    entered: GameState::GameOver,
+                },
+                check_high_score,
+            )
+            .add_systems(OnEnter(GameState::HighScoreEntry), show_initials_screen)
+            .add_systems(
+                FixedUpdate,
+                enter_initials
+                    .in_set(StepSet::Start)
+                    .run_if(in_state(GameState::HighScoreEntry)),
+            )
+            .add_systems(
+                Update,
+                update_initials_screen.run_if(in_state(GameState::HighScoreEntry)),
+            )
+            .add_systems(OnExit(GameState::HighScoreEntry), hide_initials_screen);
+    }
+}
+
+/// Players with a high score, best first, enter their initials.
+fn check_high_score(
+    mut commands: Commands,
+    game: Res<Game>,
+    high_scores: Res<HighScores>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let mut players: Vec<usize> = (0..game.players.len()).collect();
+    players.sort_by_key(|player| std::cmp::Reverse(game.players[*player].score));
+
+    // Each entry may push the following players out of the table
+    let mut table = high_scores.clone();
+    let players: VecDeque<usize> = players
+        .into_iter()
+        .filter(|player| {
+            let score = game.players[*player].score;
+            let qualifies = table.qualifies(score);
+            if qualifies {
+                table.insert(HighScore {
+                    initials: String::new(),
+                    score,
+                    date: 0,
+                });
+            }
+            qualifies
+        })
+        .collect();
+
+    if !players.is_empty() {
+        commands.insert_resource(InitialsEntry::new(players));
+        next_state.set(GameState::HighScoreEntry);
+    }
+}
+
+/// Flippers change the current initial, start validates it.
+fn enter_initials(
+    buttons: Res<ButtonInput<Button>>,
+    mut entry: ResMut<InitialsEntry>,
+    mut high_scores: ResMut<HighScores>,
+    mut next_state: ResMut<NextState<GameState>>,
+    game: Res<Game>,
+) {
+    let position = entry.position;
+    if buttons.just_pressed(Button::LeftFlipper) {
+        entry.characters[position] =
+            (entry.characters[position] + CHARACTERS.len() - 1) % CHARACTERS.len();
+    }
+    if buttons.just_pressed(Button::RightFlipper) {
+        entry.characters[position] = (entry.characters[position] + 1) % CHARACTERS.len();
+    }
+    if !buttons.just_pressed(Button::Start) {
+        return;
+    }
+
+    entry.position += 1;
+    if entry.position < INITIALS {
+        return;
+    }
+
+    let Some(player) = entry.players.pop_front() else {
+        return;
+    };
+    let date = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    high_scores.insert(HighScore {
+        initials: entry.initials(),
+        score: game.players[player].score,
+        date,
+    });
+    if let Err(error) = storage::save(STORAGE_KEY, high_scores.as_ref()) {
+        error!("Could not save high scores: {error}");
+    }
+
+    if entry.players.is_empty() {
+        next_state.set(GameState::GameOver);
+    } else {
+        let players = std::mem::take(&mut entry.players);
+        *entry = InitialsEntry::new(players);
+    }
+}
+
+fn setup_initials_screen(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::srgba(0., 0., 0., 0.7).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(InitialsScreen)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 36.,
+                        ..default()
+                    },
+                ))
+                .insert(InitialsTitle);
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 96.,
+                        ..default()
+                    },
+                ))
+                .insert(InitialsText);
+            parent.spawn(TextBundle::from_section(
+                "Flippers: change letter - Start: next letter",
+                TextStyle {
+                    font_size: 20.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn show_initials_screen(mut screen: Query<&mut Visibility, With<InitialsScreen>>) {
+    for mut visibility in &mut screen {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn hide_initials_screen(mut screen: Query<&mut Visibility, With<InitialsScreen>>) {
+    for mut visibility in &mut screen {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn update_initials_screen(
+    entry: Res<InitialsEntry>,
+    mut titles: Query<&mut Text, (With<InitialsTitle>, Without<InitialsText>)>,
+    mut texts: Query<&mut Text, (With<InitialsText>, Without<InitialsTitle>)>,
+) {
+    if !entry.is_changed() {
+        return;
+    }
+
+    if let Some(player) = entry.players.front() {
+        for mut title in &mut titles {
+            title.sections[0].value =
+                format!("HIGH SCORE! PLAYER {}, ENTER YOUR INITIALS", player + 1);
+        }
+    }
+
+    let initials = entry.initials();
+    for mut text in &mut texts {
+        text.sections[0].value = initials
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if i == entry.position {
+                    format!("[{c}]")
+                } else {
+                    format!(" {c} ")
+                }
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,170 @@
+//! Text overlay showing the game progress.
+
+use bevy::prelude::*;
+
+use crate::bonus::Bonus;
+use crate::credits::Credits;
+use crate::game::{Game, GameState};
+use crate::operator::Settings;
+use crate::rules::Rules;
+
+const FONT_SIZE: f32 = 24.;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+/// Root of the overlay shown during a game.
+#[derive(Component)]
+struct Hud;
+
+/// Text of the overlay and the information it displays.
+#[derive(Clone, Copy, Component)]
+enum HudField {
+    Player,
+    Score,
+    Ball,
+    Credits,
+    Bonus,
+    Mode,
+}
+
+#[derive(Component)]
+struct GameOverText;
+
+pub(crate) struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_hud)
+            .add_systems(
+                Update,
+                update_hud.run_if(
+                    resource_changed::<Game>
+                        .or_else(resource_changed::<Rules>)
+                        .or_else(resource_changed::<Settings>)
+                        .or_else(resource_changed::<Credits>)
+                        .or_else(resource_changed::<Bonus>),
+                ),
+            )
+            .add_systems(Update, show_hud.run_if(state_changed::<GameState>));
+    }
+}
+
+fn text(value: impl Into<String>, font_size: f32) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font_size,
+            color: TEXT_COLOR,
+            ..default()
+        },
+    )
+}
+
+fn setup_hud(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.),
+                bottom: Val::Px(10.),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(Hud)
+        .with_children(|parent| {
+            for field in [
+                HudField::Player,
+                HudField::Score,
+                HudField::Ball,
+                HudField::Credits,
+                HudField::Bonus,
+                HudField::Mode,
+            ] {
+                parent.spawn((text("", FONT_SIZE), field));
+            }
+        });
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(GameOverText)
+        .with_children(|parent| {
+            parent.spawn(text("GAME OVER", FONT_SIZE * 3.));
+        });
+}
+
+fn update_hud(
+    game: Res<Game>,
+    rules: Res<Rules>,
+    settings: Res<Settings>,
+    credits: Res<Credits>,
+    bonus: Res<Bonus>,
+    mut texts: Query<(&mut Text, &HudField)>,
+) {
+    for (mut text, field) in &mut texts {
+        text.sections[0].value = match field {
+            HudField::Player => format!("Player {} of {}", game.current + 1, game.players.len()),
+            HudField::Score => match game.players.as_slice() {
+                [player] => format!("Score {}", player.score),
+                players => players
+                    .iter()
+                    .enumerate()
+                    .map(|(i, player)| format!("P{} {}", i + 1, player.score))
+                    .collect::<Vec<_>>()
+                    .join("  "),
+            },
+            HudField::Ball => format!("Ball {} of {}", game.player().ball, settings.balls_per_game),
+            HudField::Credits => {
+                if settings.free_play {
+                    "Free play".to_string()
+                } else {
+                    format!("Credits {}", credits.credits)
+                }
+            }
+            HudField::Bonus => format!("Bonus {} x{}", bonus.value, bonus.multiplier),
+            HudField::Mode => match rules.current() {
+                Some((mode, progress)) => format!("{mode} {:.0}%", progress * 100.),
+                None => String::new(),
+            },
+        };
+    }
+}
+
+fn show_hud(
+    state: Res<State<GameState>>,
+    mut hud: Query<&mut Visibility, (With<Hud>, Without<GameOverText>)>,
+    mut game_over: Query<&mut Visibility, (With<GameOverText>, Without<Hud>)>,
+) {
+    let (show_hud, show_game_over) = match state.get() {
+        GameState::Attract | GameState::Operator => (false, false),
+        GameState::Playing | GameState::HighScoreEntry => (true, false),
+        GameState::GameOver => (true, true),
+    };
+
+    for mut visibility in &mut hud {
+        *visibility = if show_hud {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for mut visibility in &mut game_over {
+        *visibility = if show_game_over {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
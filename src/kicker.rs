@@ -0,0 +1,161 @@
+//! Kickers push the balls resting on them when the rules ask for it.
+//! Saucers hold the balls settling in them for a while, then kick them out
+//! on their own.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::physics::StepSet;
+use crate::switch::SwitchEvent;
+use crate::Ball;
+
+/// Speed in m/s under which a ball over a saucer settles in it.
+const CAPTURE_SPEED: f32 = 0.3;
+
+/// Sensor pushing the balls inside it with `impulse`, given in
+/// the kicker local coordinates.
+#[derive(Clone, Debug, Component)]
+pub(crate) struct Kicker {
+    pub(crate) name: String,
+    pub(crate) impulse: Vec3,
+}
+
+/// Fire the kicker with the given name.
+#[derive(Clone, Debug, Event)]
+pub(crate) struct Kick(pub(crate) String);
+
+/// Sensor capturing the balls settling in it. A captured ball is held
+/// `hold` seconds, then ejected with `impulse`, given in the saucer local
+/// coordinates. The switch `name` is active while a ball is held.
+#[derive(Clone, Debug, Component)]
+pub(crate) struct Saucer {
+    pub(crate) name: String,
+    pub(crate) hold: f32,
+    pub(crate) impulse: Vec3,
+}
+
+/// Ball held in `saucer` until the physics time `eject_at`, so that the
+/// hold is the same in a replay.
+#[derive(Component)]
+struct Held {
+    saucer: Entity,
+    eject_at: f32,
+}
+
+/// Ball ejected from the saucer, not captured again before it leaves it.
+#[derive(Component)]
+struct Ejected(Entity);
+
+pub(crate) struct KickerPlugin;
+
+impl Plugin for KickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Kick>().add_systems(
+            FixedUpdate,
+            (kick, capture, eject).chain().in_set(StepSet::Saucers),
+        );
+    }
+}
+
+fn kick(
+    mut commands: Commands,
+    mut kicks: EventReader<Kick>,
+    rapier_context: Res<RapierContext>,
+    kickers: Query<(Entity, &Kicker, &GlobalTransform)>,
+    balls: Query<(), With<Ball>>,
+) {
+    for Kick(name) in kicks.read() {
+        for (entity, kicker, transform) in &kickers {
+            if &kicker.name != name {
+                continue;
+            }
+            let impulse = transform.affine().transform_vector3(kicker.impulse);
+            for (first, second, intersecting) in rapier_context.intersection_pairs_with(entity) {
+                let other = if first == entity { second } else { first };
+                if intersecting && balls.contains(other) {
+                    commands.entity(other).insert(ExternalImpulse {
+                        impulse,
+                        torque_impulse: Vec3::ZERO,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn capture(
+    mut commands: Commands,
+    mut switch_events: EventWriter<SwitchEvent>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time<Fixed>>,
+    saucers: Query<(Entity, &Saucer, &Transform), Without<Ball>>,
+    mut balls: Query<
+        (Entity, &Velocity, &mut Transform, Option<&Ejected>),
+        (With<Ball>, Without<Held>),
+    >,
+) {
+    for (ball, _, _, ejected) in &balls {
+        if let Some(Ejected(saucer)) = ejected {
+            if rapier_context.intersection_pair(ball, *saucer) != Some(true) {
+                commands.entity(ball).remove::<Ejected>();
+            }
+        }
+    }
+
+    for (entity, saucer, saucer_transform) in &saucers {
+        for (first, second, intersecting) in rapier_context.intersection_pairs_with(entity) {
+            let other = if first == entity { second } else { first };
+            let Ok((ball, velocity, mut transform, ejected)) = balls.get_mut(other) else {
+                continue;
+            };
+            if !intersecting || ejected.is_some() || velocity.linvel.length() > CAPTURE_SPEED {
+                continue;
+            }
+
+            // Both are children of the table: rest the ball in the middle of
+            // the saucer, out of the simulation until it is ejected
+            transform.translation.x = saucer_transform.translation.x;
+            transform.translation.z = saucer_transform.translation.z;
+            commands
+                .entity(ball)
+                .insert(RigidBody::KinematicPositionBased)
+                .insert(Velocity::zero())
+                .insert(Held {
+                    saucer: entity,
+                    eject_at: time.elapsed_seconds() + saucer.hold,
+                });
+            switch_events.send(SwitchEvent {
+                name: saucer.name.clone(),
+                active: true,
+            });
+        }
+    }
+}
+
+fn eject(
+    mut commands: Commands,
+    mut switch_events: EventWriter<SwitchEvent>,
+    time: Res<Time<Fixed>>,
+    saucers: Query<(&Saucer, &GlobalTransform)>,
+    balls: Query<(Entity, &Held)>,
+) {
+    let now = time.elapsed_seconds();
+    for (ball, held) in &balls {
+        if now < held.eject_at {
+            continue;
+        }
+        let mut ball = commands.entity(ball);
+        ball.remove::<Held>().insert(RigidBody::Dynamic);
+        let Ok((saucer, transform)) = saucers.get(held.saucer) else {
+            continue;
+        };
+        ball.insert(Ejected(held.saucer)).insert(ExternalImpulse {
+            impulse: transform.affine().transform_vector3(saucer.impulse),
+            torque_impulse: Vec3::ZERO,
+        });
+        switch_events.send(SwitchEvent {
+            name: saucer.name.clone(),
+            active: false,
+        });
+    }
+}
@@ -0,0 +1,78 @@
+//! Lamps of the table, lit by the rules.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Time in seconds of an on/off cycle of a blinking lamp.
+const BLINK_PERIOD: f32 = 0.5;
+/// Emissive intensity of a lit lamp.
+const LIT_INTENSITY: f32 = 20.;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LampState {
+    #[default]
+    Off,
+    On,
+    Blink,
+}
+
+/// State of every lamp, by name.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct Lamps(HashMap<String, LampState>);
+
+impl Lamps {
+    pub(crate) fn set(&mut self, name: &str, state: LampState) {
+        self.0.insert(name.to_string(), state);
+    }
+
+    pub(crate) fn get(&self, name: &str) -> LampState {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+}
+
+/// Insert lit by the lamp of the same name.
+#[derive(Clone, Debug, Component)]
+pub(crate) struct Lamp {
+    pub(crate) name: String,
+    pub(crate) color: Color,
+}
+
+pub(crate) struct LampPlugin;
+
+impl Plugin for LampPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lamps>()
+            .add_systems(PostUpdate, light_lamps);
+    }
+}
+
+fn light_lamps(
+    time: Res<Time>,
+    lamps: Res<Lamps>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<(&Lamp, &Handle<StandardMaterial>)>,
+) {
+    let blink_on = time.elapsed_seconds() % BLINK_PERIOD < BLINK_PERIOD / 2.;
+    for (lamp, handle) in &query {
+        let lit = match lamps.get(&lamp.name) {
+            LampState::Off => false,
+            LampState::On => true,
+            LampState::Blink => blink_on,
+        };
+        let emissive = if lit {
+            LinearRgba::from(lamp.color) * LIT_INTENSITY
+        } else {
+            LinearRgba::BLACK
+        };
+        // Borrowing the material mutably uploads it again: only when it
+        // is switched on or off
+        let switched = materials
+            .get(handle)
+            .is_some_and(|material| material.emissive != emissive);
+        if switched {
+            if let Some(material) = materials.get_mut(handle) {
+                material.emissive = emissive;
+            }
+        }
+    }
+}
@@ -10,11 +10,35 @@ use bevy::window::WindowTheme;
 #[cfg(feature = "inspector")]
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier3d::prelude::*;
-use bevy_rapier3d::rapier::prelude::IntegrationParameters;
 use element::Side;
+use replay::Button;
+use tilt::Tilt;
 
+mod awards;
+mod bonus;
+mod credits;
+mod dmd;
 mod element;
+mod export;
+mod game;
+mod headless;
+mod highscore;
+mod hud;
+mod kicker;
+mod lamp;
+mod operator;
+mod physics;
+mod replay;
+mod rules;
+mod script;
 mod shapes;
+mod shot;
+mod sound;
+mod storage;
+mod switch;
+#[cfg(test)]
+mod tests;
+mod tilt;
 
 /// Ball group
 const BALL_GROUP: Group = Group::GROUP_1;
@@ -23,20 +47,47 @@ const TABLE_GROUP: Group = Group::GROUP_2;
 /// Flippers
 const FLIPPERS_GROUP: Group = Group::GROUP_32;
 
+/// Segments of the arcs of the meshes.
 const RESOLUTION: usize = 20;
+/// Segments of the arcs of the colliders, finer than the meshes: the ball
+/// bounces on them.
+const COLLIDER_RESOLUTION: usize = 64;
+// Sizes in meters
 const TABLE_INCLINATION: f32 = 6.5 * PI / 180.;
-const TABLE_HEIGHT: f32 = 8.;
-const TABLE_WIDTH: f32 = 5.;
-const WALL_HEIGHT: f32 = 0.3;
-const BALL_RADIUS: f32 = 0.1;
-const GUIDE_HEIGHT: f32 = TABLE_HEIGHT - 1.2;
-const FLIPPER_BIG: f32 = 0.1;
-const FLIPPER_SMALL: f32 = 0.05;
+const TABLE_HEIGHT: f32 = 1.07;
+const TABLE_WIDTH: f32 = 0.51;
+const WALL_HEIGHT: f32 = 0.04;
+/// A 27 mm ball
+const BALL_RADIUS: f32 = 0.0135;
+const GUIDE_HEIGHT: f32 = TABLE_HEIGHT - 0.12;
+const FLIPPER_BIG: f32 = 0.01;
+const FLIPPER_SMALL: f32 = 0.005;
+/// Angle in radians of a flipper from its rest to its up position
+const FLIPPER_SWING: f32 = PI / 3.;
+/// Physics steps per second, the same on every machine so that
+/// replays give the same game.
+const PHYSICS_HZ: f64 = 64.;
 
 #[derive(Component)]
 struct Ball;
 
+/// Value following `name` on the command line.
+fn arg(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
+}
+
 fn main() {
+    if std::env::args().any(|arg| arg == "--headless") {
+        headless::run();
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--export") {
+        export::run();
+        return;
+    }
+
     // Tips from https://bevy-cheatbook.github.io/features/log.html
     // this code is compiled only if debug assertions are enabled (debug mode)
     #[cfg(debug_assertions)]
@@ -55,20 +106,32 @@ fn main() {
 
     // Use WinitPlugin ??
     let mut app = App::new();
-    let app = app.add_plugins((
-        DefaultPlugins
-            .set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: "Pinball".into(),
-                    window_theme: Some(WindowTheme::Dark),
-                    ..default()
-                }),
+    let app = app.add_plugins((DefaultPlugins
+        .set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Pinball".into(),
+                window_theme: Some(WindowTheme::Dark),
                 ..default()
-            })
-            .set(log_plugin),
-        RapierPhysicsPlugin::<NoUserData>::default(),
+            }),
+            ..default()
+        })
+        .set(log_plugin),));
+    add_table(app);
+    app.add_plugins((element::ModelPlugin, sound::SoundPlugin));
+
+    // Displays and menus
+    app.add_plugins((
+        dmd::DmdPlugin,
+        highscore::HighScorePlugin,
+        hud::HudPlugin,
+        operator::OperatorPlugin,
     ));
 
+    // Last, a replay restores the settings it was recorded with
+    app.add_plugins(replay::ReplayPlugin {
+        replay: replay::recording_from_args(),
+    });
+
     #[cfg(feature = "inspector")]
     app.add_plugins(WorldInspectorPlugin::default());
 
@@ -86,22 +149,35 @@ fn main() {
     #[cfg(feature = "camera")]
     app.add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin);
 
-    app.add_systems(Startup, setup_camera_and_physics)
-        .add_systems(Startup, setup)
-        .add_systems(Update, impulse_ball)
-        .add_systems(Update, flip)
-        .run();
+    app.add_systems(Startup, setup_camera).run();
 }
 
-fn setup_camera_and_physics(mut commands: Commands, mut rapier_context: ResMut<RapierContext>) {
-    let integration = IntegrationParameters {
-        max_ccd_substeps: 1,
-        ..Default::default()
-    };
-    rapier_context.integration_parameters = integration;
+/// Physics, table and game rules, without display or sound.
+fn add_table(app: &mut App) {
+    app.add_plugins((
+        physics::PhysicsPlugin,
+        awards::AwardPlugin,
+        bonus::BonusPlugin,
+        credits::CreditsPlugin,
+        game::GamePlugin,
+        kicker::KickerPlugin,
+        lamp::LampPlugin,
+        rules::RulesPlugin,
+        script::ScriptPlugin,
+        shot::ShotPlugin,
+        switch::SwitchPlugin,
+        tilt::TiltPlugin,
+    ));
 
+    app.add_systems(Startup, setup).add_systems(
+        FixedUpdate,
+        (impulse_ball, flip).in_set(physics::StepSet::Controls),
+    );
+}
+
+fn setup_camera(mut commands: Commands) {
     let mut _entity_commands = commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0., 8., 7.).looking_at(Vec3::ZERO, Vec3::Y),
+        transform: Transform::from_xyz(0., 0.8, 0.7).looking_at(Vec3::ZERO, Vec3::Y),
         ..default()
     });
 
@@ -111,11 +187,11 @@ fn setup_camera_and_physics(mut commands: Commands, mut rapier_context: ResMut<R
     // light
     commands.spawn(PointLightBundle {
         point_light: PointLight {
-            intensity: 1500.0,
+            intensity: 15.0,
             shadows_enabled: true,
             ..default()
         },
-        transform: Transform::from_xyz(0., 8., -7.),
+        transform: Transform::from_xyz(0., 0.8, -0.7),
         ..default()
     });
 }
@@ -125,49 +201,18 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    let table = element::setup_table(&mut commands, &mut meshes, &mut materials);
-
-    // Ball
-    let mesh = Mesh::from(Sphere {
-        radius: BALL_RADIUS,
-    });
-    let ball = commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(mesh),
-            material: materials.add(Color::srgb(0., 0., 1.)),
-            ..default()
-        })
-        .insert(RigidBody::Dynamic)
-        .insert(Collider::ball(0.1))
-        .insert(ActiveEvents::COLLISION_EVENTS)
-        .insert(Restitution::coefficient(0.7))
-        //.insert(ColliderMassProperties::Density(7.86))
-        .insert(Damping {
-            linear_damping: 0.2,
-            angular_damping: 0.2,
-        })
-        .insert(CollisionGroups::new(BALL_GROUP, Group::all() - BALL_GROUP))
-        .insert(TransformBundle::from(Transform::from_xyz(
-            TABLE_WIDTH / 2. - (BALL_RADIUS + 0.01),
-            BALL_RADIUS + 0.01,
-            TABLE_HEIGHT / 2. - (BALL_RADIUS + 0.01),
-        )))
-        .insert(Ccd::enabled())
-        .insert(Dominance::group(0))
-        .insert(Ball)
-        .id();
-    commands.entity(table).add_child(ball);
+    element::setup_table(&mut commands, &mut meshes, &mut materials);
 }
 
 fn impulse_ball(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<Button>>,
     ball: Query<Entity, With<Ball>>,
     mut commands: Commands,
 ) {
     if let Ok(ball) = ball.get_single() {
-        if keyboard.pressed(KeyCode::Space) {
+        if buttons.pressed(Button::Plunger) {
             let impulse = ExternalImpulse {
-                impulse: Vec3::new(0., 0., -0.01),
+                impulse: Vec3::new(0., 0., -0.005),
                 torque_impulse: Vec3::ZERO,
             };
             commands.entity(ball).insert(impulse);
@@ -176,33 +221,46 @@ fn impulse_ball(
 }
 
 fn flip(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    query: Query<(Entity, &Side)>,
+    buttons: Res<ButtonInput<Button>>,
+    config: Res<physics::PhysicsConfig>,
+    tilt: Res<Tilt>,
+    query: Query<(
+        Entity,
+        &Side,
+        &GlobalTransform,
+        &ReadMassProperties,
+        &Damping,
+    )>,
     mut commands: Commands,
 ) {
-    for (entity, side) in &mut query.iter() {
-        let keycode = match side {
-            Side::Left => KeyCode::ControlLeft,
-            Side::Right => KeyCode::ControlRight,
+    for (entity, side, transform, mass, damping) in &query {
+        let button = match side {
+            Side::Left => Button::LeftFlipper,
+            Side::Right => Button::RightFlipper,
+        };
+        // Flippers swing up around their axis, the right one clockwise
+        let up = match side {
+            Side::Left => *transform.up(),
+            Side::Right => -*transform.up(),
         };
-        let (force, dominance) = if keyboard.pressed(keycode) {
-            (
-                ExternalForce {
-                    force: Vec3::new(0., 0., -9.),
-                    torque: Vec3::ZERO,
-                },
-                Dominance::group(1),
-            )
+        let inertia = physics::inertia_around(mass.get(), Vec3::Y);
+        // Flippers are dead after a tilt
+        let (torque, dominance) = if buttons.pressed(button) && !tilt.tilted {
+            let acceleration =
+                physics::swing_acceleration(config.flip_time, damping.angular_damping);
+            (up * inertia * acceleration, Dominance::group(1))
         } else {
-            (
-                ExternalForce {
-                    force: Vec3::new(0., 0., 4.),
-                    torque: Vec3::ZERO,
-                },
-                Dominance::group(0),
-            )
+            let acceleration =
+                physics::swing_acceleration(config.return_time, damping.angular_damping);
+            (-up * inertia * acceleration, Dominance::group(0))
         };
 
-        commands.entity(entity).insert(force).insert(dominance);
+        commands
+            .entity(entity)
+            .insert(ExternalForce {
+                force: Vec3::ZERO,
+                torque,
+            })
+            .insert(dominance);
     }
 }
@@ -0,0 +1,52 @@
+//! Statistics of the machine, kept between sessions.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Audits {
+    pub(crate) games_played: u64,
+    /// Number of scores summed in `total_score`, one per player
+    pub(crate) scores: u64,
+    pub(crate) total_score: u64,
+    pub(crate) balls_played: u64,
+    /// Time in seconds spent playing the balls
+    pub(crate) ball_time: f64,
+    /// Drained balls, by drain
+    pub(crate) drains: BTreeMap<String, u64>,
+}
+
+impl Audits {
+    pub(crate) fn average_score(&self) -> u64 {
+        self.total_score
+            .checked_div(self.scores)
+            .unwrap_or_default()
+    }
+
+    /// Average time in seconds a ball stays in play.
+    pub(crate) fn average_ball_time(&self) -> f64 {
+        if self.balls_played == 0 {
+            0.
+        } else {
+            self.ball_time / self.balls_played as f64
+        }
+    }
+
+    /// Lines shown in the operator menu.
+    pub(crate) fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Games played: {}", self.games_played),
+            format!("Average score: {}", self.average_score()),
+            format!("Average ball time: {:.1} s", self.average_ball_time()),
+        ];
+        lines.extend(
+            self.drains
+                .iter()
+                .map(|(drain, count)| format!("Drains {}: {count}", drain.replace('_', " "))),
+        );
+        lines
+    }
+}
@@ -0,0 +1,202 @@
+//! Operator menu: adjustments of the machine and audits.
+//!
+//! The menu opens with F1 from attract mode. Up and down select an
+//! adjustment, left and right change it, F1 saves and closes the menu.
+
+use bevy::prelude::*;
+
+pub(crate) use audits::*;
+pub(crate) use settings::*;
+
+use crate::game::{BallDrained, BallEnded, Game, GameState};
+use crate::storage;
+
+mod audits;
+mod settings;
+
+const SETTINGS_KEY: &str = "settings";
+const AUDITS_KEY: &str = "audits";
+const FONT_SIZE: f32 = 24.;
+
+/// Adjustment selected in the menu.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct OperatorMenu {
+    pub(crate) selected: usize,
+}
+
+#[derive(Component)]
+struct OperatorScreen;
+
+#[derive(Component)]
+struct OperatorText;
+
+pub(crate) struct OperatorPlugin;
+
+impl Plugin for OperatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load_or_default::<Settings>(SETTINGS_KEY))
+            .insert_resource(storage::load_or_default::<Audits>(AUDITS_KEY))
+            .init_resource::<OperatorMenu>()
+            .add_systems(Startup, setup_operator_screen)
+            .add_systems(Update, toggle_menu)
+            .add_systems(
+                Update,
+                (navigate_menu, update_operator_screen)
+                    .chain()
+                    .run_if(in_state(GameState::Operator)),
+            )
+            .add_systems(OnEnter(GameState::Operator), show_operator_screen)
+            .add_systems(OnExit(GameState::Operator), close_menu)
+            .add_systems(OnEnter(GameState::Playing), count_game)
+            .add_systems(Update, count_balls.run_if(in_state(GameState::Playing)))
+            .add_systems(OnExit(GameState::Playing), count_scores);
+    }
+}
+
+fn toggle_menu(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut menu: ResMut<OperatorMenu>,
+) {
+    if !keyboard.just_pressed(KeyCode::F1) {
+        return;
+    }
+
+    match state.get() {
+        GameState::Attract => {
+            *menu = OperatorMenu::default();
+            next_state.set(GameState::Operator);
+        }
+        GameState::Operator => next_state.set(GameState::Attract),
+        _ => {}
+    }
+}
+
+fn navigate_menu(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut menu: ResMut<OperatorMenu>,
+    mut settings: ResMut<Settings>,
+) {
+    let adjustments = Adjustment::all(&settings);
+    if keyboard.just_pressed(KeyCode::ArrowDown) {
+        menu.selected = (menu.selected + 1) % adjustments.len();
+    }
+    if keyboard.just_pressed(KeyCode::ArrowUp) {
+        menu.selected = (menu.selected + adjustments.len() - 1) % adjustments.len();
+    }
+
+    let adjustment = adjustments[menu.selected];
+    if keyboard.just_pressed(KeyCode::ArrowRight) {
+        adjustment.adjust(&mut settings, 1);
+    }
+    if keyboard.just_pressed(KeyCode::ArrowLeft) {
+        adjustment.adjust(&mut settings, -1);
+    }
+}
+
+fn close_menu(settings: Res<Settings>, mut screen: Query<&mut Visibility, With<OperatorScreen>>) {
+    if let Err(error) = storage::save(SETTINGS_KEY, settings.as_ref()) {
+        error!("Could not save settings: {error}");
+    }
+    for mut visibility in &mut screen {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn setup_operator_screen(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::srgba(0., 0., 0., 0.85).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(OperatorScreen)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: FONT_SIZE,
+                        ..default()
+                    },
+                ))
+                .insert(OperatorText);
+        });
+}
+
+fn show_operator_screen(mut screen: Query<&mut Visibility, With<OperatorScreen>>) {
+    for mut visibility in &mut screen {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn update_operator_screen(
+    menu: Res<OperatorMenu>,
+    settings: Res<Settings>,
+    audits: Res<Audits>,
+    mut texts: Query<&mut Text, With<OperatorText>>,
+) {
+    if !menu.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let mut lines = vec!["ADJUSTMENTS".to_string()];
+    for (i, adjustment) in Adjustment::all(&settings).into_iter().enumerate() {
+        let cursor = if i == menu.selected { ">" } else { " " };
+        lines.push(format!(
+            "{cursor} {}: {}",
+            adjustment.label(),
+            adjustment.value(&settings)
+        ));
+    }
+    lines.push(String::new());
+    lines.push("AUDITS".to_string());
+    lines.extend(audits.lines());
+
+    for mut text in &mut texts {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn count_game(mut audits: ResMut<Audits>) {
+    audits.games_played += 1;
+}
+
+/// Count the balls played and their time, a saved ball going on with the
+/// same ball, and every drain the balls fell into.
+fn count_balls(
+    time: Res<Time>,
+    mut ball_time: Local<f32>,
+    mut audits: ResMut<Audits>,
+    mut drained: EventReader<BallDrained>,
+    mut ended: EventReader<BallEnded>,
+) {
+    *ball_time += time.delta_seconds();
+    for event in drained.read() {
+        *audits.drains.entry(event.drain.clone()).or_default() += 1;
+    }
+    for _ in ended.read() {
+        audits.balls_played += 1;
+        audits.ball_time += f64::from(*ball_time);
+        *ball_time = 0.;
+    }
+}
+
+fn count_scores(game: Res<Game>, mut audits: ResMut<Audits>) {
+    for player in &game.players {
+        audits.scores += 1;
+        audits.total_score += player.score;
+    }
+    if let Err(error) = storage::save(AUDITS_KEY, audits.as_ref()) {
+        error!("Could not save audits: {error}");
+    }
+}
@@ -0,0 +1,224 @@
+//! Adjustments of the machine made by the operator.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::awards::SpecialAward;
+use crate::credits::Price;
+use crate::dmd::format_number;
+use crate::sound::MAX_VOLUME;
+
+/// Step between two replay scores in the menu.
+const REPLAY_STEP: u64 = 50_000;
+/// Points of a special awarding points, when chosen in the menu.
+const SPECIAL_POINTS: u64 = 100_000;
+
+#[derive(Clone, Debug, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub(crate) balls_per_game: u32,
+    /// Time in seconds a drained ball is given back after the launch
+    pub(crate) ball_save_time: f32,
+    /// Maximum number of extra balls awarded to a player in a game
+    pub(crate) max_extra_balls: u32,
+    pub(crate) special_award: SpecialAward,
+    /// Maximum number of specials awarded to a player in a game
+    pub(crate) max_specials: u32,
+    /// Scores awarding a replay, 0 when disabled
+    pub(crate) replay_scores: Vec<u64>,
+    /// Maximum number of replays awarded to a player in a game
+    pub(crate) max_replays: u32,
+    /// Number of warnings given before a tilt
+    pub(crate) tilt_warnings: u32,
+    /// Games start without credits
+    pub(crate) free_play: bool,
+    /// Value in coins of each coin slot
+    pub(crate) coin_values: Vec<u32>,
+    pub(crate) pricing: Vec<Price>,
+    pub(crate) max_credits: u32,
+    /// Volume levels, between 0 and [`MAX_VOLUME`]
+    pub(crate) music_volume: u32,
+    pub(crate) effects_volume: u32,
+    pub(crate) voice_volume: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            balls_per_game: 3,
+            ball_save_time: 10.,
+            max_extra_balls: 3,
+            special_award: SpecialAward::Credit,
+            max_specials: 1,
+            replay_scores: vec![500_000, 1_000_000],
+            max_replays: 2,
+            tilt_warnings: 2,
+            free_play: true,
+            coin_values: vec![1, 1, 4],
+            pricing: vec![
+                Price {
+                    coins: 1,
+                    credits: 1,
+                },
+                Price {
+                    coins: 4,
+                    credits: 5,
+                },
+            ],
+            max_credits: 30,
+            music_volume: 6,
+            effects_volume: 8,
+            voice_volume: 8,
+        }
+    }
+}
+
+/// Setting editable in the operator menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Adjustment {
+    BallsPerGame,
+    BallSaveTime,
+    MaxExtraBalls,
+    SpecialAward,
+    MaxSpecials,
+    /// Replay level, starting at 0
+    ReplayScore(usize),
+    MaxReplays,
+    TiltWarnings,
+    FreePlay,
+    MaxCredits,
+    MusicVolume,
+    EffectsVolume,
+    VoiceVolume,
+}
+
+impl Adjustment {
+    /// Adjustments of the menu, in order.
+    pub(crate) fn all(settings: &Settings) -> Vec<Adjustment> {
+        let mut adjustments = vec![
+            Adjustment::BallsPerGame,
+            Adjustment::BallSaveTime,
+            Adjustment::MaxExtraBalls,
+            Adjustment::SpecialAward,
+            Adjustment::MaxSpecials,
+        ];
+        adjustments.extend((0..settings.replay_scores.len()).map(Adjustment::ReplayScore));
+        adjustments.extend([
+            Adjustment::MaxReplays,
+            Adjustment::TiltWarnings,
+            Adjustment::FreePlay,
+            Adjustment::MaxCredits,
+            Adjustment::MusicVolume,
+            Adjustment::EffectsVolume,
+            Adjustment::VoiceVolume,
+        ]);
+        adjustments
+    }
+
+    pub(crate) fn label(self) -> String {
+        match self {
+            Adjustment::BallsPerGame => "Balls per game".to_string(),
+            Adjustment::BallSaveTime => "Ball save time".to_string(),
+            Adjustment::MaxExtraBalls => "Extra ball limit".to_string(),
+            Adjustment::SpecialAward => "Special award".to_string(),
+            Adjustment::MaxSpecials => "Special limit".to_string(),
+            Adjustment::ReplayScore(level) => format!("Replay level {}", level + 1),
+            Adjustment::MaxReplays => "Replay limit".to_string(),
+            Adjustment::TiltWarnings => "Tilt warnings".to_string(),
+            Adjustment::FreePlay => "Free play".to_string(),
+            Adjustment::MaxCredits => "Maximum credits".to_string(),
+            Adjustment::MusicVolume => "Music volume".to_string(),
+            Adjustment::EffectsVolume => "Effects volume".to_string(),
+            Adjustment::VoiceVolume => "Voice volume".to_string(),
+        }
+    }
+
+    pub(crate) fn value(self, settings: &Settings) -> String {
+        match self {
+            Adjustment::BallsPerGame => settings.balls_per_game.to_string(),
+            Adjustment::BallSaveTime => match settings.ball_save_time {
+                time if time <= 0. => "Off".to_string(),
+                time => format!("{time:.0} s"),
+            },
+            Adjustment::MaxExtraBalls => settings.max_extra_balls.to_string(),
+            Adjustment::SpecialAward => match settings.special_award {
+                SpecialAward::Points(points) => format!("{} points", format_number(points)),
+                SpecialAward::ExtraBall => "Extra ball".to_string(),
+                SpecialAward::Credit => "Credit".to_string(),
+            },
+            Adjustment::MaxSpecials => settings.max_specials.to_string(),
+            Adjustment::ReplayScore(level) => match settings.replay_scores[level] {
+                0 => "Off".to_string(),
+                score => format_number(score),
+            },
+            Adjustment::MaxReplays => settings.max_replays.to_string(),
+            Adjustment::TiltWarnings => settings.tilt_warnings.to_string(),
+            Adjustment::FreePlay => if settings.free_play { "Yes" } else { "No" }.to_string(),
+            Adjustment::MaxCredits => settings.max_credits.to_string(),
+            Adjustment::MusicVolume => settings.music_volume.to_string(),
+            Adjustment::EffectsVolume => settings.effects_volume.to_string(),
+            Adjustment::VoiceVolume => settings.voice_volume.to_string(),
+        }
+    }
+
+    /// Increase (positive `step`) or decrease the setting.
+    pub(crate) fn adjust(self, settings: &mut Settings, step: i32) {
+        let add =
+            |value: u32, min: u32, max: u32| value.saturating_add_signed(step).clamp(min, max);
+        match self {
+            Adjustment::BallsPerGame => {
+                settings.balls_per_game = add(settings.balls_per_game, 1, 10);
+            }
+            Adjustment::BallSaveTime => {
+                settings.ball_save_time = (settings.ball_save_time + step as f32).clamp(0., 30.);
+            }
+            Adjustment::MaxExtraBalls => {
+                settings.max_extra_balls = add(settings.max_extra_balls, 0, 9);
+            }
+            Adjustment::SpecialAward => {
+                let awards = [
+                    SpecialAward::Points(SPECIAL_POINTS),
+                    SpecialAward::ExtraBall,
+                    SpecialAward::Credit,
+                ];
+                let index = awards
+                    .iter()
+                    .position(|award| {
+                        std::mem::discriminant(award)
+                            == std::mem::discriminant(&settings.special_award)
+                    })
+                    .unwrap_or_default();
+                let index = (index as i32 + step).rem_euclid(awards.len() as i32);
+                settings.special_award = awards[index as usize];
+            }
+            Adjustment::MaxSpecials => {
+                settings.max_specials = add(settings.max_specials, 0, 9);
+            }
+            Adjustment::ReplayScore(level) => {
+                let score = &mut settings.replay_scores[level];
+                *score = score
+                    .saturating_add_signed(i64::from(step) * REPLAY_STEP as i64)
+                    .min(100 * REPLAY_STEP);
+            }
+            Adjustment::MaxReplays => {
+                settings.max_replays = add(settings.max_replays, 0, 9);
+            }
+            Adjustment::TiltWarnings => {
+                settings.tilt_warnings = add(settings.tilt_warnings, 0, 5);
+            }
+            Adjustment::FreePlay => settings.free_play = !settings.free_play,
+            Adjustment::MaxCredits => {
+                settings.max_credits = add(settings.max_credits, 1, 99);
+            }
+            Adjustment::MusicVolume => {
+                settings.music_volume = add(settings.music_volume, 0, MAX_VOLUME);
+            }
+            Adjustment::EffectsVolume => {
+                settings.effects_volume = add(settings.effects_volume, 0, MAX_VOLUME);
+            }
+            Adjustment::VoiceVolume => {
+                settings.voice_volume = add(settings.voice_volume, 0, MAX_VOLUME);
+            }
+        }
+    }
+}
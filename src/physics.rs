@@ -0,0 +1,270 @@
+//! Physics of the table in SI units: meters, kilograms, seconds.
+//!
+//! The constants that give the feel of the table are in `PhysicsConfig`,
+//! applied again whenever it changes.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_rapier3d::rapier::prelude::IntegrationParameters;
+
+use crate::element::{PinballMaterial, Side};
+use crate::{Ball, FLIPPER_SWING, PHYSICS_HZ};
+
+/// How bouncy and how slippery a material is. Of the rules of two
+/// colliders in contact, `Max` wins over `Multiply`, `Min` and `Average`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Contact {
+    pub(crate) restitution: f32,
+    pub(crate) restitution_rule: CoefficientCombineRule,
+    pub(crate) friction: f32,
+    pub(crate) friction_rule: CoefficientCombineRule,
+}
+
+impl Contact {
+    const fn new(
+        restitution: f32,
+        restitution_rule: CoefficientCombineRule,
+        friction: f32,
+        friction_rule: CoefficientCombineRule,
+    ) -> Self {
+        Self {
+            restitution,
+            restitution_rule,
+            friction,
+            friction_rule,
+        }
+    }
+
+    fn restitution(&self) -> Restitution {
+        Restitution {
+            coefficient: self.restitution,
+            combine_rule: self.restitution_rule,
+        }
+    }
+
+    fn friction(&self) -> Friction {
+        Friction {
+            coefficient: self.friction,
+            combine_rule: self.friction_rule,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Resource)]
+pub(crate) struct PhysicsConfig {
+    /// Acceleration of gravity in m/s², pulling the ball down the table
+    /// along its inclination
+    pub(crate) gravity: f32,
+    /// Mass of the ball in kg, a 27 mm steel ball
+    pub(crate) ball_mass: f32,
+    /// Linear and angular damping of the ball, for the rolling resistance
+    pub(crate) ball_damping: f32,
+    pub(crate) ball: Contact,
+    /// Density of the flippers in kg/m³
+    pub(crate) flipper_density: f32,
+    /// Time in s for a flipper at rest to swing up while its button is
+    /// pressed
+    pub(crate) flip_time: f32,
+    /// Time in s for the spring to pull a flipper back down
+    pub(crate) return_time: f32,
+    pub(crate) metal: Contact,
+    pub(crate) rubber: Contact,
+    pub(crate) wood: Contact,
+    pub(crate) plastic: Contact,
+    pub(crate) glass: Contact,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        use CoefficientCombineRule::*;
+
+        Self {
+            gravity: 9.81,
+            ball_mass: 0.0806,
+            ball_damping: 0.2,
+            ball: Contact::new(0.6, Average, 0.2, Average),
+            // Plastic
+            flipper_density: 1200.,
+            // A real flipper swings up in about 50 ms
+            flip_time: 0.05,
+            return_time: 0.1,
+            // The ball slides along the guides without bouncing off them
+            metal: Contact::new(0.2, Min, 0.15, Min),
+            // and bounces off the posts and the flippers, which grip it
+            rubber: Contact::new(0.9, Max, 0.9, Max),
+            // Waxed playfield
+            wood: Contact::new(0.3, Min, 0.2, Average),
+            plastic: Contact::new(0.4, Min, 0.2, Average),
+            glass: Contact::new(0.3, Min, 0.1, Min),
+        }
+    }
+}
+
+impl PhysicsConfig {
+    pub(crate) fn contact(&self, material: PinballMaterial) -> Contact {
+        match material {
+            PinballMaterial::Metal => self.metal,
+            PinballMaterial::Rubber => self.rubber,
+            PinballMaterial::Wood => self.wood,
+            PinballMaterial::Plastic => self.plastic,
+            PinballMaterial::Glass => self.glass,
+        }
+    }
+}
+
+/// Angular acceleration in rad/s² swinging a flipper from rest through
+/// `FLIPPER_SWING` in `time`, against its angular `damping`.
+pub(crate) fn swing_acceleration(time: f32, damping: f32) -> f32 {
+    if damping <= f32::EPSILON {
+        return 2. * FLIPPER_SWING / time.powi(2);
+    }
+    // The angular speed tends to acceleration / damping
+    FLIPPER_SWING * damping.powi(2) / (damping * time - 1. + (-damping * time).exp())
+}
+
+/// Moment of inertia of a body around its local `axis` through its origin.
+pub(crate) fn inertia_around(mass: &MassProperties, axis: Vec3) -> f32 {
+    let frame = mass.principal_inertia_local_frame;
+    let at_center: f32 = [Vec3::X, Vec3::Y, Vec3::Z]
+        .into_iter()
+        .zip(mass.principal_inertia.to_array())
+        .map(|(principal, inertia)| inertia * (frame * principal).dot(axis).powi(2))
+        .sum();
+    let offset = mass.local_center_of_mass.reject_from_normalized(axis);
+    at_center + mass.mass * offset.length_squared()
+}
+
+/// Systems of a physics step, in this order. The ones pushing the balls
+/// and the flippers run before the Rapier step, the ones reading its
+/// contacts after it: a replay gives the same game.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum StepSet {
+    /// Coins inserted
+    Coins,
+    /// Start button and initials entry
+    Start,
+    /// Nudges pushing the ball
+    Nudge,
+    /// Plunger and flippers
+    Controls,
+    /// Switches the balls went over during the step
+    Switches,
+    /// Kickers and saucers holding the balls
+    Saucers,
+    /// Shots made of the switches
+    Shots,
+    /// Rules and modes
+    Rules,
+    /// Rules of the script
+    Script,
+    /// Extra balls, specials and replays
+    Awards,
+    /// Score, drains and the next ball
+    Game,
+    /// End-of-ball bonus
+    Bonus,
+    /// End of the tilt with the ball
+    Tilt,
+}
+
+/// Rapier stepped at `PHYSICS_HZ`, configured by `PhysicsConfig`.
+pub(crate) struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule())
+            .insert_resource(Time::<Fixed>::from_hz(PHYSICS_HZ))
+            .insert_resource(TimestepMode::Fixed {
+                dt: 1. / PHYSICS_HZ as f32,
+                substeps: 1,
+            })
+            .init_resource::<PhysicsConfig>()
+            .configure_sets(
+                FixedUpdate,
+                (
+                    (
+                        StepSet::Coins,
+                        StepSet::Start,
+                        StepSet::Nudge,
+                        StepSet::Controls,
+                    )
+                        .chain()
+                        .before(PhysicsSet::SyncBackend),
+                    (
+                        StepSet::Switches,
+                        StepSet::Saucers,
+                        StepSet::Shots,
+                        StepSet::Rules,
+                        StepSet::Script,
+                        StepSet::Awards,
+                        StepSet::Game,
+                        StepSet::Bonus,
+                        StepSet::Tilt,
+                    )
+                        .chain()
+                        .after(PhysicsSet::Writeback),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    configure.run_if(resource_changed::<PhysicsConfig>),
+                    apply_config,
+                )
+                    .before(PhysicsSet::SyncBackend),
+            );
+    }
+}
+
+fn configure(
+    config: Res<PhysicsConfig>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut rapier_context: ResMut<RapierContext>,
+) {
+    rapier_config.gravity = Vec3::NEG_Y * config.gravity;
+    rapier_context.integration_parameters = IntegrationParameters {
+        max_ccd_substeps: 1,
+        ..default()
+    };
+}
+
+/// Give the balls, the flippers and the elements their physical
+/// properties when they are spawned, and again when the config changes.
+fn apply_config(
+    mut commands: Commands,
+    config: Res<PhysicsConfig>,
+    balls: Query<(Entity, Ref<Ball>)>,
+    flippers: Query<(Entity, Ref<Side>)>,
+    materials: Query<(Entity, Ref<PinballMaterial>)>,
+) {
+    let all = config.is_changed();
+    for (entity, ball) in &balls {
+        if all || ball.is_added() {
+            commands
+                .entity(entity)
+                .insert(ColliderMassProperties::Mass(config.ball_mass))
+                .insert(Damping {
+                    linear_damping: config.ball_damping,
+                    angular_damping: config.ball_damping,
+                })
+                .insert(config.ball.restitution())
+                .insert(config.ball.friction());
+        }
+    }
+    for (entity, side) in &flippers {
+        if all || side.is_added() {
+            commands
+                .entity(entity)
+                .insert(ColliderMassProperties::Density(config.flipper_density));
+        }
+    }
+    for (entity, material) in &materials {
+        if all || material.is_added() {
+            let contact = config.contact(*material);
+            commands
+                .entity(entity)
+                .insert(contact.restitution())
+                .insert(contact.friction());
+        }
+    }
+}
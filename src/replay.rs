@@ -0,0 +1,360 @@
+//! Recording of the cabinet buttons and their replay.
+//!
+//! The buttons are sampled once a physics step into `ButtonInput<Button>`,
+//! read by the game systems, which run at the same fixed step as the
+//! physics. Each game is recorded from its start with the settings, credits
+//! and high scores of the machine: replaying the buttons held at each step
+//! gives the same game, as long as the build and the assets are the same.
+//!
+//! The last game is saved when it is over. `--replay` replays it,
+//! `--replay <file>` a saved one.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::SystemTime;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::credits::{Credits, COIN_SLOTS};
+use crate::game::GameState;
+use crate::highscore::HighScores;
+use crate::operator::Settings;
+use crate::script;
+use crate::{storage, PHYSICS_HZ};
+
+const STORAGE_KEY: &str = "replay";
+
+/// Button of the cabinet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Button {
+    LeftFlipper,
+    RightFlipper,
+    Plunger,
+    NudgeLeft,
+    NudgeRight,
+    Start,
+    /// Coin slot, starting at 0
+    Coin(usize),
+}
+
+impl Button {
+    fn all() -> impl Iterator<Item = Button> {
+        [
+            Button::LeftFlipper,
+            Button::RightFlipper,
+            Button::Plunger,
+            Button::NudgeLeft,
+            Button::NudgeRight,
+            Button::Start,
+        ]
+        .into_iter()
+        .chain((0..COIN_SLOTS.len()).map(Button::Coin))
+    }
+
+    fn key(self) -> KeyCode {
+        match self {
+            Button::LeftFlipper => KeyCode::ControlLeft,
+            Button::RightFlipper => KeyCode::ControlRight,
+            Button::Plunger => KeyCode::Space,
+            Button::NudgeLeft => KeyCode::KeyZ,
+            Button::NudgeRight => KeyCode::Slash,
+            Button::Start => KeyCode::Digit1,
+            Button::Coin(slot) => COIN_SLOTS[slot],
+        }
+    }
+}
+
+/// Inputs of a game, with the state of the machine when it started.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Recording {
+    /// Seed of the random numbers of the rules
+    pub(crate) seed: u64,
+    settings: Settings,
+    credits: Credits,
+    high_scores: HighScores,
+    /// Number of physics steps of the game
+    steps: usize,
+    /// Buttons held from the given physics step on
+    buttons: Vec<(usize, Vec<Button>)>,
+}
+
+impl Recording {
+    /// Inputs given as the buttons held from a time in seconds since the
+    /// start of the game.
+    pub(crate) fn scripted(seed: u64, mut inputs: Vec<(f64, Vec<Button>)>) -> Self {
+        inputs.sort_by(|(first, _), (second, _)| first.total_cmp(second));
+        let mut recording = Recording { seed, ..default() };
+        for (time, buttons) in inputs {
+            recording.extend_to(time);
+            recording.buttons.push((recording.steps, buttons));
+        }
+        recording
+    }
+
+    /// Add physics steps up to `seconds`, the buttons staying as they are.
+    pub(crate) fn extend_to(&mut self, seconds: f64) {
+        let steps = (seconds * PHYSICS_HZ).ceil();
+        if steps > 0. {
+            self.steps = self.steps.max(steps as usize);
+        }
+    }
+
+    fn buttons_at(&self, step: usize) -> &[Button] {
+        let position = self.buttons.partition_point(|(start, _)| *start <= step);
+        match position {
+            0 => &[],
+            position => &self.buttons[position - 1].1,
+        }
+    }
+
+    /// Duration of the recording in seconds.
+    pub(crate) fn duration(&self) -> f64 {
+        self.steps as f64 / PHYSICS_HZ
+    }
+}
+
+/// Xorshift random numbers, the same for the same seed.
+#[derive(Clone, Debug)]
+pub(crate) struct Rng(u64);
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // The state must not be 0
+        Self(seed | 1)
+    }
+
+    /// Between 0 included and `max` excluded, 0 when `max` is 0.
+    pub(crate) fn below(&mut self, max: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0.checked_rem(max).unwrap_or_default()
+    }
+}
+
+/// Game being recorded or replayed.
+#[derive(Debug, Resource)]
+pub(crate) struct Session {
+    pub(crate) recording: Recording,
+    replaying: bool,
+    /// The replay is over
+    replayed: bool,
+    /// The storage was made read only by the replay, and is writable
+    /// again when it ends
+    restore_storage: bool,
+    /// Physics step of the game
+    step: usize,
+    /// Buttons pressed since the last physics step, held during the next
+    /// one even if they are already released
+    pressed: Vec<Button>,
+}
+
+impl Session {
+    /// The whole recording has been replayed.
+    pub(crate) fn finished(&self) -> bool {
+        self.replayed
+    }
+
+    fn end_replay(&mut self, commands: &mut Commands) {
+        info!("Replay ended");
+        self.replaying = false;
+        self.replayed = true;
+        if self.restore_storage {
+            storage::set_read_only(false);
+        }
+        commands.insert_resource(TimeUpdateStrategy::Automatic);
+    }
+}
+
+/// Recording to replay given on the command line, if any.
+pub(crate) fn recording_from_args() -> Option<Recording> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--replay");
+    args.next()?;
+    let result = match args.next().filter(|arg| !arg.starts_with("--")) {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|content| ron::from_str(&content).map_err(|error| error.to_string())),
+        None => storage::load(STORAGE_KEY)
+            .map_err(|error| error.to_string())
+            .and_then(|recording| recording.ok_or("no game recorded yet".to_string())),
+    };
+    match result {
+        Ok(recording) => Some(recording),
+        Err(error) => {
+            error!("Could not load the replay: {error}");
+            None
+        }
+    }
+}
+
+/// Record the games, or replay `replay`.
+pub(crate) struct ReplayPlugin {
+    pub(crate) replay: Option<Recording>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let replaying = self.replay.is_some();
+        let restore_storage = replaying && !storage::is_read_only();
+        let recording = match &self.replay {
+            Some(recording) => {
+                info!("Replaying {:.0} s", recording.duration());
+                // Nothing saved would be from a real game
+                storage::set_read_only(true);
+                // One physics step a frame, as fast as the machine goes
+                app.insert_resource(recording.settings.clone())
+                    .insert_resource(recording.credits.clone())
+                    .insert_resource(recording.high_scores.clone())
+                    .insert_resource(TimeUpdateStrategy::ManualDuration(
+                        Time::<Fixed>::from_hz(PHYSICS_HZ).timestep(),
+                    ))
+                    .add_systems(Startup, pause_table)
+                    .add_systems(
+                        Update,
+                        start_replay
+                            .run_if(in_state(GameState::Attract).and_then(script::script_ready)),
+                    );
+                recording.clone()
+            }
+            None => Recording::default(),
+        };
+
+        app.insert_resource(Session {
+            recording,
+            replaying,
+            replayed: false,
+            restore_storage,
+            step: 0,
+            pressed: Vec::new(),
+        })
+        .init_resource::<ButtonInput<Button>>()
+        .add_systems(PreUpdate, latch_buttons.after(InputSystem))
+        .add_systems(FixedFirst, update_buttons)
+        .add_systems(OnEnter(GameState::Playing), start_recording)
+        .add_systems(OnEnter(GameState::GameOver), save_recording);
+    }
+}
+
+/// The table stands still until the replay starts, however long the
+/// rules take to load.
+fn pause_table(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = false;
+}
+
+/// The recording starts with the game, once the rules are loaded: the
+/// credit is already taken.
+fn start_replay(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    rapier_config.physics_pipeline_active = true;
+    next_state.set(GameState::Playing);
+}
+
+/// Start recording the game with the state of the machine.
+pub(crate) fn start_recording(
+    mut session: ResMut<Session>,
+    settings: Res<Settings>,
+    credits: Res<Credits>,
+    high_scores: Res<HighScores>,
+) {
+    session.step = 0;
+    if session.replaying {
+        return;
+    }
+    session.recording = Recording {
+        seed: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default(),
+        settings: settings.clone(),
+        credits: credits.clone(),
+        high_scores: high_scores.clone(),
+        ..default()
+    };
+}
+
+/// Keep the buttons pressed during this frame for the next physics step.
+fn latch_buttons(keyboard: Res<ButtonInput<KeyCode>>, mut session: ResMut<Session>) {
+    if session.replaying {
+        return;
+    }
+    for button in Button::all() {
+        if keyboard.just_pressed(button.key()) && !session.pressed.contains(&button) {
+            session.pressed.push(button);
+        }
+    }
+}
+
+/// Set the buttons held during this physics step, from the keyboard or
+/// the recording, and record them during a game.
+fn update_buttons(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut buttons: ResMut<ButtonInput<Button>>,
+    mut session: ResMut<Session>,
+) {
+    let playing = *state.get() == GameState::Playing;
+    if session.replaying && playing && session.step >= session.recording.steps {
+        session.end_replay(&mut commands);
+    }
+
+    let held: Vec<Button> = if session.replaying {
+        // Only the recorded buttons are pressed, once the game started
+        if playing {
+            session.recording.buttons_at(session.step).to_vec()
+        } else {
+            Vec::new()
+        }
+    } else {
+        Button::all()
+            .filter(|button| keyboard.pressed(button.key()) || session.pressed.contains(button))
+            .collect()
+    };
+    session.pressed.clear();
+
+    buttons.clear();
+    for button in Button::all() {
+        if held.contains(&button) {
+            buttons.press(button);
+        } else {
+            buttons.release(button);
+        }
+    }
+
+    if !playing {
+        return;
+    }
+    if !session.replaying {
+        let step = session.step;
+        let recording = &mut session.recording;
+        let changed = match recording.buttons.last() {
+            Some((_, last)) => *last != held,
+            None => !held.is_empty(),
+        };
+        if changed {
+            recording.buttons.push((step, held));
+        }
+        recording.steps = step + 1;
+    }
+    session.step += 1;
+}
+
+/// Save the game over, or end its replay.
+fn save_recording(mut commands: Commands, mut session: ResMut<Session>) {
+    if session.replaying {
+        session.end_replay(&mut commands);
+        return;
+    }
+    if let Err(error) = storage::save(STORAGE_KEY, &session.recording) {
+        error!("Could not save the replay: {error}");
+    }
+}
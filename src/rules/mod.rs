@@ -0,0 +1,158 @@
+//! Rule engine: a stack of modes started by the switches, holding
+//! their own missions and timers, awarding points and lighting lamps.
+
+use bevy::prelude::*;
+
+pub(crate) use mode::*;
+
+use crate::dmd::{Animation, Dmd};
+use crate::game::{BallEnded, GameState, PlayerUp, Points};
+use crate::lamp::{LampState, Lamps};
+use crate::physics::StepSet;
+use crate::shot::ShotEvent;
+use crate::sound::Callout;
+use crate::switch::SwitchEvent;
+
+mod mode;
+mod table;
+
+pub(crate) struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Rules::new(table::table_modes()))
+            .add_event::<Outcome>()
+            .add_systems(OnEnter(GameState::Playing), reset_rules)
+            .add_systems(
+                FixedUpdate,
+                (
+                    switch_player,
+                    handle_switches,
+                    handle_shots,
+                    tick_modes,
+                    end_ball,
+                    apply_outcomes,
+                    light_lamps,
+                )
+                    .chain()
+                    .in_set(StepSet::Rules)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), turn_off_lamps);
+    }
+}
+
+fn reset_rules(mut rules: ResMut<Rules>) {
+    rules.reset();
+}
+
+fn switch_player(
+    mut rules: ResMut<Rules>,
+    mut lamps: ResMut<Lamps>,
+    mut player_up: EventReader<PlayerUp>,
+) {
+    for event in player_up.read() {
+        for (name, _) in rules.lamps() {
+            lamps.set(&name, LampState::Off);
+        }
+        rules.switch_player(event.previous, event.current);
+    }
+}
+
+fn handle_switches(
+    mut rules: ResMut<Rules>,
+    mut switch_events: EventReader<SwitchEvent>,
+    mut outcomes: EventWriter<Outcome>,
+) {
+    for event in switch_events.read() {
+        if event.active {
+            outcomes.send_batch(rules.handle(&Trigger::Switch(event.name.clone())));
+        }
+    }
+}
+
+fn handle_shots(
+    mut rules: ResMut<Rules>,
+    mut shot_events: EventReader<ShotEvent>,
+    mut outcomes: EventWriter<Outcome>,
+) {
+    for event in shot_events.read() {
+        outcomes.send_batch(rules.handle(&Trigger::Shot(event.name.clone())));
+    }
+}
+
+fn tick_modes(time: Res<Time>, mut rules: ResMut<Rules>, mut outcomes: EventWriter<Outcome>) {
+    outcomes.send_batch(rules.tick(time.delta()));
+}
+
+fn end_ball(
+    mut rules: ResMut<Rules>,
+    mut ended: EventReader<BallEnded>,
+    mut outcomes: EventWriter<Outcome>,
+) {
+    for _ in ended.read() {
+        outcomes.send_batch(rules.end_ball());
+    }
+}
+
+fn apply_outcomes(
+    mut outcomes: EventReader<Outcome>,
+    mut points: EventWriter<Points>,
+    mut callouts: EventWriter<Callout>,
+    mut dmd: ResMut<Dmd>,
+) {
+    for outcome in outcomes.read() {
+        match outcome {
+            Outcome::Points(value) => {
+                points.send(Points(*value));
+            }
+            Outcome::Jackpot(value) => {
+                points.send(Points(*value));
+                callouts.send(Callout {
+                    name: "jackpot".to_string(),
+                    priority: 2,
+                });
+                dmd.play(Animation::Blink {
+                    text: "JACKPOT".to_string(),
+                    scale: 2,
+                    period: 0.3,
+                    duration: 1.5,
+                });
+            }
+            Outcome::ModeStarted(name) => {
+                callouts.send(Callout {
+                    name: name.to_lowercase().replace(' ', "_"),
+                    priority: 1,
+                });
+                dmd.play(Animation::Blink {
+                    text: name.to_uppercase(),
+                    scale: 2,
+                    period: 0.5,
+                    duration: 1.5,
+                });
+            }
+            Outcome::MissionCompleted(name) => {
+                dmd.play(Animation::Scroll {
+                    text: name.to_uppercase(),
+                    scale: 2,
+                    speed: 120.,
+                });
+            }
+            Outcome::ModeEnded(name) => {
+                debug!("Mode {name} ended");
+            }
+        }
+    }
+}
+
+fn light_lamps(rules: Res<Rules>, mut lamps: ResMut<Lamps>) {
+    for (name, state) in rules.lamps() {
+        lamps.set(&name, state);
+    }
+}
+
+fn turn_off_lamps(rules: Res<Rules>, mut lamps: ResMut<Lamps>) {
+    for (name, _) in rules.lamps() {
+        lamps.set(&name, LampState::Off);
+    }
+}
@@ -0,0 +1,291 @@
+//! Definition of the modes and their progress during a game.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::lamp::LampState;
+
+/// Something happening on the table the rules react to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Trigger {
+    /// A switch has been activated
+    Switch(String),
+    /// A shot has been completed
+    Shot(String),
+}
+
+/// Goal of a mode.
+#[derive(Clone, Debug)]
+pub(crate) struct Mission {
+    pub(crate) name: String,
+    pub(crate) trigger: Trigger,
+    /// Number of triggers needed to complete the mission
+    pub(crate) count: u32,
+    /// Points awarded when the mission is completed
+    pub(crate) points: u64,
+    /// Lamp blinking while the mission is running and lit once completed
+    pub(crate) lamp: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Mode {
+    pub(crate) name: String,
+    /// Modes with a higher priority are displayed and have
+    /// precedence on the lamps.
+    pub(crate) priority: i32,
+    /// Trigger starting the mode. Base modes (without trigger)
+    /// run during the whole game.
+    pub(crate) start: Option<Trigger>,
+    /// Time limit in seconds
+    pub(crate) duration: Option<f32>,
+    /// Points awarded for each trigger while the mode is running
+    pub(crate) scores: Vec<(Trigger, u64)>,
+    pub(crate) missions: Vec<Mission>,
+    /// Points awarded when all missions are completed. It ends the
+    /// mode, or restarts its missions for a base mode.
+    pub(crate) jackpot: u64,
+    /// Lamps lit while the mode is running
+    pub(crate) lamps: Vec<String>,
+    /// Music played while the mode is the highest running one with
+    /// music, `music/<name>.ogg` in the assets
+    pub(crate) music: Option<String>,
+}
+
+/// What happened when the rules processed a trigger or time passed.
+#[derive(Clone, Debug, PartialEq, Eq, Event)]
+pub(crate) enum Outcome {
+    Points(u64),
+    ModeStarted(String),
+    ModeEnded(String),
+    MissionCompleted(String),
+    Jackpot(u64),
+}
+
+#[derive(Clone, Debug)]
+struct RunningMode {
+    /// Index in the mode definitions
+    mode: usize,
+    timer: Option<Timer>,
+    /// Number of triggers received by each mission
+    progress: Vec<u32>,
+}
+
+/// Modes of the table and the stack of the running ones.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct Rules {
+    modes: Vec<Mode>,
+    /// Running modes of the player up, sorted by priority, highest first
+    running: Vec<RunningMode>,
+    /// Modes kept for the other players, by player index
+    players: HashMap<usize, Vec<RunningMode>>,
+}
+
+impl Rules {
+    pub(crate) fn new(modes: Vec<Mode>) -> Self {
+        Self {
+            modes,
+            running: Vec::new(),
+            players: HashMap::new(),
+        }
+    }
+
+    /// Stop every mode of every player and start the base modes.
+    pub(crate) fn reset(&mut self) {
+        self.players.clear();
+        self.start_base_modes();
+    }
+
+    /// Keep the progress of the `previous` player and restore the one
+    /// of the `current` player.
+    pub(crate) fn switch_player(&mut self, previous: usize, current: usize) {
+        let running = std::mem::take(&mut self.running);
+        self.players.insert(previous, running);
+
+        match self.players.remove(&current) {
+            Some(running) => self.running = running,
+            None => self.start_base_modes(),
+        }
+    }
+
+    fn start_base_modes(&mut self) {
+        self.running.clear();
+        for index in 0..self.modes.len() {
+            if self.modes[index].start.is_none() {
+                self.start(index);
+            }
+        }
+    }
+
+    fn start(&mut self, index: usize) {
+        let mode = &self.modes[index];
+        let running = RunningMode {
+            mode: index,
+            timer: mode
+                .duration
+                .map(|duration| Timer::from_seconds(duration, TimerMode::Once)),
+            progress: vec![0; mode.missions.len()],
+        };
+        let position = self
+            .running
+            .iter()
+            .position(|other| self.modes[other.mode].priority < mode.priority)
+            .unwrap_or(self.running.len());
+        self.running.insert(position, running);
+    }
+
+    fn is_running(&self, index: usize) -> bool {
+        self.running.iter().any(|running| running.mode == index)
+    }
+
+    /// Let the running modes react to `trigger`, then start the
+    /// modes it triggers.
+    pub(crate) fn handle(&mut self, trigger: &Trigger) -> Vec<Outcome> {
+        let mut outcomes = Vec::new();
+        let mut ended = Vec::new();
+
+        for (position, running) in self.running.iter_mut().enumerate() {
+            let mode = &self.modes[running.mode];
+            for (score_trigger, points) in &mode.scores {
+                if score_trigger == trigger {
+                    outcomes.push(Outcome::Points(*points));
+                }
+            }
+
+            let mut completed = false;
+            for (mission, progress) in mode.missions.iter().zip(running.progress.iter_mut()) {
+                if &mission.trigger == trigger && *progress < mission.count {
+                    *progress += 1;
+                    if *progress == mission.count {
+                        completed = true;
+                        outcomes.push(Outcome::MissionCompleted(mission.name.clone()));
+                        outcomes.push(Outcome::Points(mission.points));
+                    }
+                }
+            }
+
+            let all_completed = mode
+                .missions
+                .iter()
+                .zip(&running.progress)
+                .all(|(mission, progress)| *progress >= mission.count);
+            if completed && all_completed {
+                outcomes.push(Outcome::Jackpot(mode.jackpot));
+                if mode.start.is_none() {
+                    running.progress.fill(0);
+                } else {
+                    ended.push(position);
+                }
+            }
+        }
+
+        for position in ended.into_iter().rev() {
+            let running = self.running.remove(position);
+            outcomes.push(Outcome::ModeEnded(self.modes[running.mode].name.clone()));
+        }
+
+        for index in 0..self.modes.len() {
+            if self.modes[index].start.as_ref() == Some(trigger) && !self.is_running(index) {
+                self.start(index);
+                outcomes.push(Outcome::ModeStarted(self.modes[index].name.clone()));
+            }
+        }
+
+        outcomes
+    }
+
+    /// Advance the timers of the running modes, ending the ones
+    /// running out of time.
+    pub(crate) fn tick(&mut self, delta: Duration) -> Vec<Outcome> {
+        let mut outcomes = Vec::new();
+        let modes = &self.modes;
+        self.running.retain_mut(|running| {
+            let Some(timer) = running.timer.as_mut() else {
+                return true;
+            };
+            if timer.tick(delta).finished() {
+                outcomes.push(Outcome::ModeEnded(modes[running.mode].name.clone()));
+                false
+            } else {
+                true
+            }
+        });
+
+        outcomes
+    }
+
+    /// End the timed modes, which last at most until the end of the ball.
+    pub(crate) fn end_ball(&mut self) -> Vec<Outcome> {
+        let mut outcomes = Vec::new();
+        let modes = &self.modes;
+        self.running.retain(|running| {
+            if running.timer.is_none() {
+                return true;
+            }
+            outcomes.push(Outcome::ModeEnded(modes[running.mode].name.clone()));
+            false
+        });
+
+        outcomes
+    }
+
+    /// State of the lamps controlled by the rules. Lamps of modes
+    /// with a higher priority override the others.
+    pub(crate) fn lamps(&self) -> Vec<(String, LampState)> {
+        let mut lamps = Vec::new();
+        for mode in &self.modes {
+            lamps.extend(mode.lamps.iter().map(|lamp| (lamp.clone(), LampState::Off)));
+            for mission in &mode.missions {
+                if let Some(lamp) = &mission.lamp {
+                    lamps.push((lamp.clone(), LampState::Off));
+                }
+            }
+        }
+
+        for running in self.running.iter().rev() {
+            let mode = &self.modes[running.mode];
+            lamps.extend(mode.lamps.iter().map(|lamp| (lamp.clone(), LampState::On)));
+            for (mission, progress) in mode.missions.iter().zip(&running.progress) {
+                if let Some(lamp) = &mission.lamp {
+                    let state = if *progress >= mission.count {
+                        LampState::On
+                    } else {
+                        LampState::Blink
+                    };
+                    lamps.push((lamp.clone(), state));
+                }
+            }
+        }
+
+        lamps
+    }
+
+    /// Music of the running mode with the highest priority having one.
+    pub(crate) fn music(&self) -> Option<&str> {
+        self.running
+            .iter()
+            .find_map(|running| self.modes[running.mode].music.as_deref())
+    }
+
+    /// Name and progress (between 0 and 1) of the running mode
+    /// with the highest priority.
+    pub(crate) fn current(&self) -> Option<(&str, f32)> {
+        let running = self.running.first()?;
+        let mode = &self.modes[running.mode];
+        let total: u32 = mode.missions.iter().map(|mission| mission.count).sum();
+        let done: u32 = mode
+            .missions
+            .iter()
+            .zip(&running.progress)
+            .map(|(mission, progress)| (*progress).min(mission.count))
+            .sum();
+        let progress = if total == 0 {
+            0.
+        } else {
+            done as f32 / total as f32
+        };
+
+        Some((&mode.name, progress))
+    }
+}
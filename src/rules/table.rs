@@ -0,0 +1,82 @@
+//! Rules of the table.
+
+use super::{Mission, Mode, Trigger};
+
+fn switch(name: &str) -> Trigger {
+    Trigger::Switch(name.to_string())
+}
+
+fn shot(name: &str) -> Trigger {
+    Trigger::Shot(name.to_string())
+}
+
+pub(crate) fn table_modes() -> Vec<Mode> {
+    vec![
+        Mode {
+            name: "Lanes".to_string(),
+            priority: 0,
+            start: None,
+            duration: None,
+            scores: vec![
+                (switch("launch"), 100),
+                (switch("left_lane"), 500),
+                (switch("right_lane"), 500),
+                (shot("top_lanes"), 2_000),
+                (switch("scoop"), 1_000),
+            ],
+            missions: vec![
+                Mission {
+                    name: "Left lane".to_string(),
+                    trigger: switch("left_lane"),
+                    count: 1,
+                    points: 1_000,
+                    lamp: Some("left_lane".to_string()),
+                },
+                Mission {
+                    name: "Right lane".to_string(),
+                    trigger: switch("right_lane"),
+                    count: 1,
+                    points: 1_000,
+                    lamp: Some("right_lane".to_string()),
+                },
+            ],
+            jackpot: 10_000,
+            lamps: Vec::new(),
+            music: Some("main".to_string()),
+        },
+        Mode {
+            name: "Skill shot".to_string(),
+            priority: 10,
+            start: Some(switch("launch")),
+            duration: Some(5.),
+            scores: Vec::new(),
+            missions: vec![Mission {
+                name: "Skill shot".to_string(),
+                trigger: shot("full_plunge"),
+                count: 1,
+                points: 0,
+                lamp: None,
+            }],
+            jackpot: 25_000,
+            lamps: vec!["mode".to_string()],
+            music: Some("skill_shot".to_string()),
+        },
+        Mode {
+            name: "Scoop".to_string(),
+            priority: 5,
+            start: Some(switch("scoop")),
+            duration: Some(20.),
+            scores: vec![(switch("left_lane"), 1_000), (switch("right_lane"), 1_000)],
+            missions: vec![Mission {
+                name: "Both lanes".to_string(),
+                trigger: shot("top_lanes"),
+                count: 1,
+                points: 5_000,
+                lamp: None,
+            }],
+            jackpot: 15_000,
+            lamps: vec!["mode".to_string()],
+            music: None,
+        },
+    ]
+}
@@ -0,0 +1,156 @@
+//! Functions available to the scripts.
+//!
+//! Scripts run synchronously while the world is borrowed by the calling
+//! system, so the functions only record commands that are applied to
+//! the world after the call.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rhai::{Engine, EvalAltResult};
+
+use crate::awards::Award;
+use crate::lamp::LampState;
+use crate::replay::Rng;
+
+/// Action requested by a script.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Command {
+    AddScore(u64),
+    SetLamp(String, LampState),
+    PlaySound(String),
+    /// Name and priority
+    Callout(String, i32),
+    Kick(String),
+    BallSave(f32),
+    StartTimer(String, f32),
+    StopTimer(String),
+    Award(Award),
+    AddBonus(u64),
+    AdvanceBonusMultiplier,
+}
+
+/// State shared between the engine functions and the systems.
+#[derive(Debug, Default)]
+pub(crate) struct Context {
+    pub(crate) commands: Vec<Command>,
+    /// Score of the player, updated when the commands are taken, so it
+    /// leaves out the points of the commands not applied yet
+    pub(crate) score: u64,
+    /// Ball save time set by the operator
+    pub(crate) ball_save_time: f32,
+    /// Seeded by the session, so that replays draw the same numbers
+    pub(crate) rng: Rng,
+}
+
+pub(crate) type SharedContext = Arc<Mutex<Context>>;
+
+fn push(context: &SharedContext, command: Command) {
+    context.lock().unwrap().commands.push(command);
+}
+
+/// Duration given by a script, rejected unless it is a number of seconds,
+/// not negative, that a timer can hold.
+fn seconds(seconds: f64) -> Result<f32, Box<EvalAltResult>> {
+    let seconds = seconds as f32;
+    match Duration::try_from_secs_f32(seconds) {
+        Ok(_) => Ok(seconds),
+        Err(_) => Err(format!("Invalid duration: {seconds} seconds").into()),
+    }
+}
+
+/// Create an engine with the table functions registered.
+pub(crate) fn engine(context: &SharedContext) -> Engine {
+    let mut engine = Engine::new();
+
+    let ctx = context.clone();
+    engine.register_fn("score", move || ctx.lock().unwrap().score as i64);
+
+    let ctx = context.clone();
+    engine.register_fn("random", move |max: i64| {
+        ctx.lock().unwrap().rng.below(max.max(0) as u64) as i64
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("add_score", move |points: i64| {
+        push(&ctx, Command::AddScore(points.max(0) as u64));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("set_lamp", move |name: &str, state: &str| {
+        let state = match state {
+            "on" => LampState::On,
+            "blink" => LampState::Blink,
+            _ => LampState::Off,
+        };
+        push(&ctx, Command::SetLamp(name.to_string(), state));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("play_sound", move |name: &str| {
+        push(&ctx, Command::PlaySound(name.to_string()));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("callout", move |name: &str, priority: i64| {
+        push(&ctx, Command::Callout(name.to_string(), priority as i32));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("kick", move |name: &str| {
+        push(&ctx, Command::Kick(name.to_string()));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("ball_save_time", move || {
+        ctx.lock().unwrap().ball_save_time as f64
+    });
+
+    let ctx = context.clone();
+    engine.register_fn(
+        "ball_save",
+        move |duration: f64| -> Result<(), Box<EvalAltResult>> {
+            push(&ctx, Command::BallSave(seconds(duration)?));
+            Ok(())
+        },
+    );
+
+    let ctx = context.clone();
+    engine.register_fn(
+        "start_timer",
+        move |name: &str, duration: f64| -> Result<(), Box<EvalAltResult>> {
+            push(
+                &ctx,
+                Command::StartTimer(name.to_string(), seconds(duration)?),
+            );
+            Ok(())
+        },
+    );
+
+    let ctx = context.clone();
+    engine.register_fn("stop_timer", move |name: &str| {
+        push(&ctx, Command::StopTimer(name.to_string()));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("award_extra_ball", move || {
+        push(&ctx, Command::Award(Award::ExtraBall));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("award_special", move || {
+        push(&ctx, Command::Award(Award::Special));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("add_bonus", move |points: i64| {
+        push(&ctx, Command::AddBonus(points.max(0) as u64));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("advance_bonus_multiplier", move || {
+        push(&ctx, Command::AdvanceBonusMultiplier);
+    });
+
+    engine
+}
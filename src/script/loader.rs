@@ -0,0 +1,45 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use thiserror::Error;
+
+/// Source code of a Rhai script.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct Script {
+    pub(crate) source: String,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ScriptLoaderError {
+    #[error("Could not read script: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Script is not valid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+#[derive(Default)]
+pub(crate) struct ScriptLoader;
+
+impl AssetLoader for ScriptLoader {
+    type Asset = Script;
+    type Settings = ();
+    type Error = ScriptLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(Script {
+            source: String::from_utf8(bytes)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+}
@@ -0,0 +1,303 @@
+//! Table rules written in [Rhai](https://rhai.rs).
+//!
+//! The script `assets/table/rules.rhai` lives alongside the table assets
+//! and is reloaded when it changes (with the `debug` feature). It may
+//! define the following callbacks, `this` being an object map keeping
+//! the script state of the player up during a game:
+//!
+//! * `init()`: called on the first ball of each player or when the
+//!   script is reloaded
+//! * `on_switch(name)`: a switch has been activated
+//! * `on_shot(name, combo)`: a shot has been completed, `combo` being the
+//!   number of consecutive shots without touching a flipper
+//! * `on_timer(name)`: a timer started with `start_timer` expired
+//!
+//! See [`api`] for the functions scripts can call.
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rhai::{CallFnOptions, Dynamic, Engine, FuncArgs, Map, Scope, AST};
+
+use crate::awards::Award;
+use crate::bonus::Bonus;
+use crate::dmd::{Animation, Dmd};
+use crate::game::{BallSave, Game, GameState, PlayerUp, Points};
+use crate::kicker::Kick;
+use crate::lamp::Lamps;
+use crate::operator::Settings;
+use crate::physics::StepSet;
+use crate::replay::{self, Rng, Session};
+use crate::shot::ShotEvent;
+use crate::sound::{Callout, PlaySound};
+use crate::switch::SwitchEvent;
+
+pub(crate) use api::*;
+pub(crate) use loader::*;
+
+mod api;
+mod loader;
+
+const SCRIPT_PATH: &str = "table/rules.rhai";
+
+/// Compiled script and its state.
+#[derive(Resource)]
+pub(crate) struct Scripting {
+    engine: Engine,
+    context: SharedContext,
+    handle: Handle<Script>,
+    ast: Option<AST>,
+    /// The script was compiled, even if it has errors
+    compiled: bool,
+    /// Object map bound to `this` in the callbacks
+    state: Dynamic,
+    /// State kept for the other players, by player index
+    players: HashMap<usize, Dynamic>,
+}
+
+impl Scripting {
+    fn new(handle: Handle<Script>) -> Self {
+        let context = SharedContext::default();
+        Self {
+            engine: api::engine(&context),
+            context,
+            handle,
+            ast: None,
+            compiled: false,
+            state: Map::new().into(),
+            players: HashMap::new(),
+        }
+    }
+
+    fn compile(&mut self, source: &str) {
+        self.compiled = true;
+        match self.engine.compile(source) {
+            Ok(ast) => {
+                info!("Script {SCRIPT_PATH} loaded");
+                self.ast = Some(ast);
+                self.init();
+            }
+            Err(error) => {
+                error!("Could not compile {SCRIPT_PATH}: {error}");
+            }
+        }
+    }
+
+    /// Reset the script state.
+    fn init(&mut self) {
+        self.state = Map::new().into();
+        self.call("init", ());
+    }
+
+    /// Keep the state of the `previous` player and restore the one
+    /// of the `current` player, initialized on their first ball.
+    fn switch_player(&mut self, previous: usize, current: usize) {
+        let state = std::mem::take(&mut self.state);
+        self.players.insert(previous, state);
+        match self.players.remove(&current) {
+            Some(state) => self.state = state,
+            None => self.init(),
+        }
+    }
+
+    /// Call a callback of the script, if it is defined.
+    pub(crate) fn call(&mut self, name: &str, args: impl FuncArgs) {
+        let Some(ast) = &self.ast else {
+            return;
+        };
+        if !ast.iter_functions().any(|function| function.name == name) {
+            return;
+        }
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            ast,
+            name,
+            args,
+        );
+        if let Err(error) = result {
+            error!("Script error in {name}: {error}");
+        }
+    }
+
+    fn take_commands(&mut self, score: u64, settings: &Settings) -> Vec<Command> {
+        let mut context = self.context.lock().unwrap();
+        context.score = score;
+        context.ball_save_time = settings.ball_save_time;
+        std::mem::take(&mut context.commands)
+    }
+}
+
+/// Timers started by the script.
+#[derive(Debug, Default, Resource)]
+struct ScriptTimers(HashMap<String, Timer>);
+
+pub(crate) struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Script>()
+            .init_asset_loader::<ScriptLoader>()
+            .init_resource::<ScriptTimers>()
+            .add_systems(Startup, load_script)
+            .add_systems(Update, reload_script)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                start_script.after(replay::start_recording),
+            )
+            .add_systems(
+                FixedUpdate,
+                (switch_player, on_switch, on_shot, on_timer, apply_commands)
+                    .chain()
+                    .in_set(StepSet::Script)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// The script is compiled or could not be loaded: the rules will not
+/// change before it is modified.
+pub(crate) fn script_ready(scripting: Res<Scripting>, asset_server: Res<AssetServer>) -> bool {
+    scripting.compiled
+        || matches!(
+            asset_server.load_state(&scripting.handle),
+            LoadState::Failed(_)
+        )
+}
+
+fn load_script(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Scripting::new(asset_server.load(SCRIPT_PATH)));
+}
+
+fn reload_script(
+    mut events: EventReader<AssetEvent<Script>>,
+    mut scripting: ResMut<Scripting>,
+    scripts: Res<Assets<Script>>,
+) {
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&scripting.handle)
+            || event.is_modified(&scripting.handle)
+        {
+            if let Some(script) = scripts.get(&scripting.handle) {
+                scripting.compile(&script.source);
+            }
+        }
+    }
+}
+
+/// Each game draws its random numbers from the seed of its recording.
+fn start_script(
+    mut scripting: ResMut<Scripting>,
+    mut timers: ResMut<ScriptTimers>,
+    session: Res<Session>,
+) {
+    scripting.context.lock().unwrap().rng = Rng::new(session.recording.seed);
+    timers.0.clear();
+    scripting.players.clear();
+    scripting.init();
+}
+
+/// Timers started during the ball of a player do not fire during the ball
+/// of the next one.
+fn switch_player(
+    mut scripting: ResMut<Scripting>,
+    mut timers: ResMut<ScriptTimers>,
+    mut player_up: EventReader<PlayerUp>,
+) {
+    for event in player_up.read() {
+        timers.0.clear();
+        scripting.switch_player(event.previous, event.current);
+    }
+}
+
+fn on_switch(mut scripting: ResMut<Scripting>, mut switch_events: EventReader<SwitchEvent>) {
+    for event in switch_events.read() {
+        if event.active {
+            scripting.call("on_switch", (event.name.clone(),));
+        }
+    }
+}
+
+fn on_shot(mut scripting: ResMut<Scripting>, mut shot_events: EventReader<ShotEvent>) {
+    for event in shot_events.read() {
+        scripting.call("on_shot", (event.name.clone(), event.combo as i64));
+    }
+}
+
+fn on_timer(time: Res<Time>, mut scripting: ResMut<Scripting>, mut timers: ResMut<ScriptTimers>) {
+    let mut expired = Vec::new();
+    timers.0.retain(|name, timer| {
+        if timer.tick(time.delta()).finished() {
+            expired.push(name.clone());
+            false
+        } else {
+            true
+        }
+    });
+
+    for name in expired {
+        scripting.call("on_timer", (name,));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_commands(
+    mut scripting: ResMut<Scripting>,
+    mut timers: ResMut<ScriptTimers>,
+    mut lamps: ResMut<Lamps>,
+    mut ball_save: ResMut<BallSave>,
+    mut points: EventWriter<Points>,
+    mut sounds: EventWriter<PlaySound>,
+    mut callouts: EventWriter<Callout>,
+    mut kicks: EventWriter<Kick>,
+    mut awards: EventWriter<Award>,
+    mut bonus: ResMut<Bonus>,
+    mut dmd: ResMut<Dmd>,
+    game: Res<Game>,
+    settings: Res<Settings>,
+) {
+    for command in scripting.take_commands(game.player().score, &settings) {
+        match command {
+            Command::AddScore(value) => {
+                points.send(Points(value));
+            }
+            Command::SetLamp(name, state) => lamps.set(&name, state),
+            Command::PlaySound(name) => {
+                sounds.send(PlaySound(name));
+            }
+            Command::Callout(name, priority) => {
+                callouts.send(Callout { name, priority });
+            }
+            Command::Kick(name) => {
+                kicks.send(Kick(name));
+            }
+            Command::BallSave(seconds) => ball_save.start(seconds),
+            Command::StartTimer(name, seconds) => {
+                timers
+                    .0
+                    .insert(name, Timer::from_seconds(seconds, TimerMode::Once));
+            }
+            Command::StopTimer(name) => {
+                timers.0.remove(&name);
+            }
+            Command::Award(award) => {
+                awards.send(award);
+            }
+            Command::AddBonus(points) => bonus.add(points),
+            Command::AdvanceBonusMultiplier => {
+                if bonus.advance_multiplier() {
+                    dmd.play(Animation::Blink {
+                        text: format!("BONUS {}X", bonus.multiplier),
+                        scale: 2,
+                        period: 0.3,
+                        duration: 1.,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Rubber band stretched around posts: arcs around the posts joined by the
+//! outer tangents of each pair of neighbours.
+
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use thiserror::Error;
+
+use super::{debug_check, MeshElements, Surface, Y_NEGATIF, Y_POSITIF};
+
+#[derive(Clone, Copy, Debug, Error)]
+pub(crate) enum BandError {
+    #[error("A band needs two posts or more")]
+    TooFewPosts,
+    #[error("Posts {0} and {1} are inside one another")]
+    Overlap(usize, usize),
+    #[error("The loop turns inward at post {0}")]
+    Concave(usize),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Band {
+    /// Centers of the posts in the x z plane and their radii, in the order
+    /// the band goes around them. The posts must make a convex loop.
+    pub(crate) posts: Vec<(Vec2, f32)>,
+    /// Thickness of the rubber around the posts
+    pub(crate) thickness: f32,
+    /// Height (in the upper direction)
+    pub(crate) height: f32,
+    /// Number of segments of the arc around each post
+    pub(crate) resolution: usize,
+}
+
+impl Band {
+    /// Posts turning counter clockwise in the x z plane, so that the
+    /// outside of the band is on the right. Each turn of the loop must be
+    /// under half a turn, the way the band goes.
+    fn posts(&self) -> Result<Vec<(Vec2, f32)>, BandError> {
+        if self.posts.len() < 2 {
+            return Err(BandError::TooFewPosts);
+        }
+        let mut posts = self.posts.clone();
+        let area: f32 = (0..posts.len())
+            .map(|i| posts[i].0.perp_dot(posts[(i + 1) % posts.len()].0))
+            .sum();
+        if area < 0. {
+            posts.reverse();
+        }
+        // Two posts always make a loop, turning by half a turn at each
+        if posts.len() > 2 {
+            for i in 0..posts.len() {
+                let previous = posts[(i + posts.len() - 1) % posts.len()].0;
+                let next = posts[(i + 1) % posts.len()].0;
+                let (incoming, outgoing) = (posts[i].0 - previous, next - posts[i].0);
+                let turn = incoming.perp_dot(outgoing).atan2(incoming.dot(outgoing));
+                if !(0. ..PI).contains(&turn) {
+                    return Err(BandError::Concave(i));
+                }
+            }
+        }
+        Ok(posts)
+    }
+
+    /// Normal of the outer tangent from each post to the next one.
+    fn tangents(&self, posts: &[(Vec2, f32)]) -> Result<Vec<Vec2>, BandError> {
+        (0..posts.len())
+            .map(|i| {
+                let next = (i + 1) % posts.len();
+                let ((from, from_radius), (to, to_radius)) = (posts[i], posts[next]);
+                let distance = from.distance(to);
+                let sine = (from_radius - to_radius) / distance;
+                if sine.is_nan() || sine.abs() >= 1. {
+                    return Err(BandError::Overlap(i, next));
+                }
+                let direction = (to - from) / distance;
+                let outside = Vec2::new(direction.y, -direction.x);
+                Ok(direction * sine + outside * (1. - sine * sine).sqrt())
+            })
+            .collect()
+    }
+
+    /// Points of the band at `offset` from the posts with their normals,
+    /// going around each post from the tangent coming in to the tangent
+    /// going out.
+    fn outline(&self, offset: f32) -> Result<Vec<(Vec2, Vec2)>, BandError> {
+        let posts = self.posts()?;
+        let tangents = self.tangents(&posts)?;
+
+        let mut outline = Vec::with_capacity(posts.len() * (self.resolution + 1));
+        for (i, (center, radius)) in posts.iter().enumerate() {
+            let incoming = tangents[(i + posts.len() - 1) % posts.len()];
+            let outgoing = tangents[i];
+            let start = incoming.to_angle();
+            let angle = (outgoing.to_angle() - start).rem_euclid(TAU);
+            for step in 0..=self.resolution {
+                let normal = Vec2::from_angle(start + angle * step as f32 / self.resolution as f32);
+                outline.push((*center + normal * (radius + offset), normal));
+            }
+        }
+        Ok(outline)
+    }
+
+    /// Exact collider: a cylinder of rubber around each post and a cuboid
+    /// along each tangent.
+    pub(crate) fn collider(&self) -> Result<Collider, BandError> {
+        let posts = self.posts()?;
+        let tangents = self.tangents(&posts)?;
+        let half_height = self.height / 2.;
+
+        let mut shapes = Vec::with_capacity(posts.len() * 2);
+        for (i, (center, radius)) in posts.iter().enumerate() {
+            shapes.push((
+                Vec3::new(center.x, 0., center.y),
+                Quat::IDENTITY,
+                Collider::cylinder(half_height, radius + self.thickness),
+            ));
+
+            let (next, next_radius) = posts[(i + 1) % posts.len()];
+            let normal = tangents[i];
+            let from = *center + normal * (radius + self.thickness / 2.);
+            let to = next + normal * (next_radius + self.thickness / 2.);
+            let middle = (from + to) / 2.;
+            let direction = to - from;
+            shapes.push((
+                Vec3::new(middle.x, 0., middle.y),
+                Quat::from_rotation_y((-direction.y).atan2(direction.x)),
+                Collider::cuboid(direction.length() / 2., half_height, self.thickness / 2.),
+            ));
+        }
+        Ok(Collider::compound(shapes))
+    }
+
+    /// Side of the band between `from` and `to`, facing out or in.
+    fn side(&self, from: (Vec2, Vec2), to: (Vec2, Vec2), out: bool) -> MeshElements {
+        let y = self.height / 2.;
+        let direction = if out { 1. } else { -1. };
+        let ((from, from_normal), (to, to_normal)) = (from, to);
+        let from_normal = from_normal * direction;
+        let to_normal = to_normal * direction;
+
+        let vertices = vec![
+            [from.x, -y, from.y],
+            [to.x, -y, to.y],
+            [to.x, y, to.y],
+            [from.x, y, from.y],
+        ];
+        let normals = vec![
+            [from_normal.x, 0., from_normal.y],
+            [to_normal.x, 0., to_normal.y],
+            [to_normal.x, 0., to_normal.y],
+            [from_normal.x, 0., from_normal.y],
+        ];
+        let indices = if out {
+            vec![0, 2, 1, 0, 3, 2]
+        } else {
+            vec![0, 1, 2, 0, 2, 3]
+        };
+
+        MeshElements {
+            vertices,
+            normals,
+            indices,
+        }
+    }
+
+    /// Top or bottom of the band between two inner points and the outer
+    /// ones facing them.
+    fn top_bottom(&self, inner: [Vec2; 2], outer: [Vec2; 2], top: bool) -> MeshElements {
+        let (y, normal, indices) = if top {
+            (self.height / 2., Y_POSITIF, vec![0, 2, 1, 0, 3, 2])
+        } else {
+            (-self.height / 2., Y_NEGATIF, vec![0, 1, 2, 0, 2, 3])
+        };
+
+        MeshElements {
+            vertices: vec![
+                [inner[0].x, y, inner[0].y],
+                [outer[0].x, y, outer[0].y],
+                [outer[1].x, y, outer[1].y],
+                [inner[1].x, y, inner[1].y],
+            ],
+            normals: vec![normal; 4],
+            indices,
+        }
+    }
+}
+
+impl TryFrom<Band> for MeshElements {
+    type Error = BandError;
+
+    fn try_from(value: Band) -> Result<Self, Self::Error> {
+        let inner = value.outline(0.)?;
+        let outer = value.outline(value.thickness)?;
+
+        let mut band = MeshElements {
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+        };
+        for i in 0..inner.len() {
+            let next = (i + 1) % inner.len();
+            band += value.side(outer[i], outer[next], true);
+            band += value.side(inner[i], inner[next], false);
+            for top in [true, false] {
+                band += value.top_bottom(
+                    [inner[i].0, inner[next].0],
+                    [outer[i].0, outer[next].0],
+                    top,
+                );
+            }
+        }
+
+        Ok(band)
+    }
+}
+
+impl TryFrom<Band> for Mesh {
+    type Error = BandError;
+
+    fn try_from(value: Band) -> Result<Self, Self::Error> {
+        let band = MeshElements::try_from(value)?;
+        debug_check("band", || band.validate(Surface::Closed));
+        Ok(band.into())
+    }
+}
@@ -4,14 +4,20 @@
 use std::f32::consts::PI;
 
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
 use thiserror::Error;
 
-use super::{MeshElements, X_POSITIF, Y_NEGATIF, Y_POSITIF, Z_NEGATIF};
+use super::{debug_check, MeshElements, Surface, X_POSITIF, Y_NEGATIF, Y_POSITIF, Z_POSITIF};
+
+/// Thickness of the collider of a band, which has none.
+const BAND_THICKNESS: f32 = 0.002;
 
 #[derive(Clone, Copy, Debug, Error)]
 pub(crate) enum BanisterError {
     #[error("Angle must be between 0 and PI/2 (inclusive)")]
     OutsideRange,
+    #[error("The collider has no segments")]
+    NoSegments,
 }
 
 /// Enum that allow to control where the
@@ -27,6 +33,7 @@ pub(crate) enum Origin {
     MaxXMaxZ,
 }
 
+#[derive(Clone, Copy, Debug)]
 pub(crate) struct Ellipse {
     /// Show the quarter of rect
     pub(crate) rectangle: bool,
@@ -62,6 +69,64 @@ impl Default for Ellipse {
 }
 
 impl Ellipse {
+    fn check_angles(&self) -> Result<(), BanisterError> {
+        if self.first_angle < 0.
+            || self.first_angle > PI / 2.
+            || self.second_angle < 0.
+            || self.second_angle > PI / 2.
+        {
+            return Err(BanisterError::OutsideRange);
+        }
+        Ok(())
+    }
+
+    /// Collider of the exact shape, its arc cut in `resolution` segments
+    /// whatever the resolution of the mesh. With its rectangle, each
+    /// segment closes a prism with the corner of the rectangle, otherwise
+    /// it is a thin wall. Fails without any segment, with a resolution of
+    /// 0 or a flat ellipse.
+    pub(crate) fn collider(&self, resolution: usize) -> Result<Collider, BanisterError> {
+        self.check_angles()?;
+        let (angle_min, angle_max) = self.min_max_angle();
+        let offset = self.axis_offsets();
+        let arc: Vec<Vec3> = (0..=resolution)
+            .map(|i| {
+                let angle = angle_min + (angle_max - angle_min) * i as f32 / resolution as f32;
+                Vec3::new(self.x * angle.cos(), 0., self.z * angle.sin()) + offset
+            })
+            .collect();
+        let corner = Vec3::new(self.x * angle_min.cos(), 0., self.z * angle_max.sin()) + offset;
+        let up = Vec3::Y * self.thickness;
+
+        let segments = arc
+            .windows(2)
+            .filter_map(|segment| {
+                let (from, to) = (segment[0], segment[1]);
+                if self.rectangle {
+                    let points = [corner, from, to, corner + up, from + up, to + up];
+                    let prism = Collider::convex_hull(&points)?;
+                    Some((Vec3::ZERO, Quat::IDENTITY, prism))
+                } else {
+                    let direction = to - from;
+                    let wall = Collider::cuboid(
+                        direction.length() / 2.,
+                        self.thickness / 2.,
+                        BAND_THICKNESS / 2.,
+                    );
+                    Some((
+                        (from + to + up) / 2.,
+                        Quat::from_rotation_y((-direction.z).atan2(direction.x)),
+                        wall,
+                    ))
+                }
+            })
+            .collect::<Vec<_>>();
+        if segments.is_empty() {
+            return Err(BanisterError::NoSegments);
+        }
+        Ok(Collider::compound(segments))
+    }
+
     fn min_max_angle(&self) -> (f32, f32) {
         if self.first_angle < self.second_angle {
             (self.first_angle, self.second_angle)
@@ -165,10 +230,10 @@ impl Ellipse {
             self.z * angle_max.sin(),
         ]);
         vertices.push([self.x * angle_max.cos(), 0., self.z * angle_max.sin()]);
-        normals.push(Z_NEGATIF);
-        normals.push(Z_NEGATIF);
-        normals.push(Z_NEGATIF);
-        normals.push(Z_NEGATIF);
+        normals.push(Z_POSITIF);
+        normals.push(Z_POSITIF);
+        normals.push(Z_POSITIF);
+        normals.push(Z_POSITIF);
 
         indices.push(0);
         indices.push(1);
@@ -273,17 +338,11 @@ impl Ellipse {
     }
 }
 
-impl TryFrom<Ellipse> for Mesh {
+impl TryFrom<Ellipse> for MeshElements {
     type Error = BanisterError;
 
     fn try_from(value: Ellipse) -> Result<Self, Self::Error> {
-        if value.first_angle < 0.
-            || value.first_angle > PI / 2.
-            || value.second_angle < 0.
-            || value.second_angle > PI / 2.
-        {
-            return Err(BanisterError::OutsideRange);
-        }
+        value.check_angles()?;
 
         let angle_increment =
             (value.first_angle - value.second_angle).abs() / value.resolution as f32;
@@ -310,6 +369,22 @@ impl TryFrom<Ellipse> for Mesh {
         // Center the origin
         ellipse += value.axis_offsets();
 
+        Ok(ellipse)
+    }
+}
+
+impl TryFrom<Ellipse> for Mesh {
+    type Error = BanisterError;
+
+    fn try_from(value: Ellipse) -> Result<Self, Self::Error> {
+        // Without its rectangle, it's a band seen from both sides
+        let surface = if value.rectangle {
+            Surface::Closed
+        } else {
+            Surface::Open
+        };
+        let ellipse = MeshElements::try_from(value)?;
+        debug_check("ellipse", || ellipse.validate(surface));
         Ok(ellipse.into())
     }
 }
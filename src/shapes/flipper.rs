@@ -1,9 +1,11 @@
 use std::f32::consts::PI;
 
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
 
-use crate::shapes::{MeshElements, Y_NEGATIF, Y_POSITIF};
+use crate::shapes::{debug_check, MeshElements, Surface, Y_NEGATIF, Y_POSITIF};
 
+#[derive(Clone, Copy, Debug)]
 pub(crate) struct Flipper {
     x: f32,
     radius_min: f32,
@@ -23,6 +25,59 @@ impl Flipper {
         }
     }
 
+    /// Tapered capsule as high as the mesh: a cylinder at each end and the
+    /// convex hull of their tangents, without the facets of the arcs.
+    pub(crate) fn collider(&self) -> Collider {
+        let distance_centers = self.x - (self.radius_min + self.radius_max);
+        let tangente = (self.radius_max - self.radius_min) / (2. * distance_centers);
+        let alpha = tangente.atan() * 2.;
+        let half_height = self.thickness / 2.;
+        let small_center = Vec3::new(
+            distance_centers,
+            0.,
+            (self.radius_max - self.radius_min) / 2.,
+        );
+
+        // Where the sides of the mesh leave the arcs
+        let sides = [
+            Vec3::new(0., 0., self.radius_max),
+            Vec3::new(
+                self.radius_max * alpha.sin(),
+                0.,
+                -self.radius_max * alpha.cos(),
+            ),
+            small_center
+                + Vec3::new(
+                    self.radius_min * alpha.sin(),
+                    0.,
+                    -self.radius_min * alpha.cos(),
+                ),
+            small_center + Vec3::new(0., 0., self.radius_min),
+        ];
+        let points: Vec<Vec3> = [-half_height, half_height]
+            .into_iter()
+            .flat_map(|y| sides.map(|side| side + Vec3::Y * y))
+            .collect();
+
+        Collider::compound(vec![
+            (
+                Vec3::ZERO,
+                Quat::IDENTITY,
+                Collider::cylinder(half_height, self.radius_max),
+            ),
+            (
+                small_center,
+                Quat::IDENTITY,
+                Collider::cylinder(half_height, self.radius_min),
+            ),
+            (
+                Vec3::ZERO,
+                Quat::IDENTITY,
+                Collider::convex_hull(&points).expect("sides of the flipper"),
+            ),
+        ])
+    }
+
     fn border(&self) -> MeshElements {
         let x_distance_centers = self.x - (self.radius_min + self.radius_max);
         let tangente = (self.radius_max - self.radius_min) / (2. * x_distance_centers);
@@ -180,7 +235,7 @@ impl Flipper {
     }
 }
 
-impl From<Flipper> for Mesh {
+impl From<Flipper> for MeshElements {
     fn from(value: Flipper) -> Self {
         // Down
         let mut down = value.left_arc(0.);
@@ -240,6 +295,14 @@ impl From<Flipper> for Mesh {
 
         down += Vec3::new(0., -value.thickness / 2., 0.);
 
-        down.into()
+        down
+    }
+}
+
+impl From<Flipper> for Mesh {
+    fn from(value: Flipper) -> Self {
+        let flipper = MeshElements::from(value);
+        debug_check("flipper", || flipper.validate(Surface::Closed));
+        flipper.into()
     }
 }
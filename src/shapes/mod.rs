@@ -1,14 +1,18 @@
+pub(crate) use band::*;
 use bevy::prelude::{Mesh, Vec3};
-use bevy::render::mesh::Indices;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::PrimitiveTopology;
 pub(crate) use elipse::*;
 pub(crate) use flipper::*;
 pub(crate) use table::*;
+pub(crate) use validate::*;
 
+mod band;
 mod elipse;
 mod flipper;
 mod table;
+mod validate;
 
 const X_NEGATIF: [f32; 3] = [-1., 0., 0.];
 const X_POSITIF: [f32; 3] = [1., 0., 0.];
@@ -19,8 +23,9 @@ const Y_POSITIF: [f32; 3] = [0., 1., 0.];
 const Z_NEGATIF: [f32; 3] = [0., 0., -1.];
 const Z_POSITIF: [f32; 3] = [0., 0., 1.];
 
+/// Vertices with their normals and the indices of the triangles.
 #[derive(Clone, Debug)]
-struct MeshElements {
+pub(crate) struct MeshElements {
     pub(crate) vertices: Vec<[f32; 3]>,
     pub(crate) normals: Vec<[f32; 3]>,
     pub(crate) indices: Vec<u32>,
@@ -67,3 +72,32 @@ impl From<MeshElements> for Mesh {
         .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, value.normals)
     }
 }
+
+impl TryFrom<&Mesh> for MeshElements {
+    type Error = MeshError;
+
+    fn try_from(mesh: &Mesh) -> Result<Self, Self::Error> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return Err(MeshError::Topology);
+        }
+        let Some(VertexAttributeValues::Float32x3(vertices)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return Err(MeshError::MissingAttribute(Mesh::ATTRIBUTE_POSITION.name));
+        };
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            return Err(MeshError::MissingAttribute(Mesh::ATTRIBUTE_NORMAL.name));
+        };
+        let indices = match mesh.indices() {
+            Some(indices) => indices.iter().map(|index| index as u32).collect(),
+            None => (0..vertices.len() as u32).collect(),
+        };
+        Ok(MeshElements {
+            vertices: vertices.clone(),
+            normals: normals.clone(),
+            indices,
+        })
+    }
+}
@@ -4,9 +4,17 @@ use bevy::prelude::*;
 use bevy::render::mesh::Indices;
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::PrimitiveTopology;
+use bevy_rapier3d::prelude::*;
 
-use super::{X_NEGATIF, X_POSITIF, Y_POSITIF, Z_NEGATIF, Z_POSITIF};
+use super::{
+    debug_check, validate_mesh, Surface, X_NEGATIF, X_POSITIF, Y_POSITIF, Z_NEGATIF, Z_POSITIF,
+};
 
+/// Thickness of the floor and of the walls of the collider, outside of the
+/// mesh, enough to stop a fast ball.
+const WALL_THICKNESS: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug)]
 pub(crate) struct Table {
     height: f32,
     width: f32,
@@ -21,6 +29,49 @@ impl Table {
             thickness: wall_height,
         }
     }
+
+    /// Floor and walls as cuboids, their inner faces on the mesh.
+    pub(crate) fn collider(&self) -> Collider {
+        let half_width = self.width / 2.;
+        let half_height = self.height / 2.;
+        let half_wall = self.thickness / 2.;
+        let half_thickness = WALL_THICKNESS / 2.;
+        let floor = Collider::cuboid(
+            half_width + WALL_THICKNESS,
+            half_thickness,
+            half_height + WALL_THICKNESS,
+        );
+        let side = Collider::cuboid(half_thickness, half_wall, half_height + WALL_THICKNESS);
+        let end = Collider::cuboid(half_width + WALL_THICKNESS, half_wall, half_thickness);
+
+        Collider::compound(vec![
+            (
+                Vec3::new(0., -half_wall - half_thickness, 0.),
+                Quat::IDENTITY,
+                floor,
+            ),
+            (
+                Vec3::new(-half_width - half_thickness, 0., 0.),
+                Quat::IDENTITY,
+                side.clone(),
+            ),
+            (
+                Vec3::new(half_width + half_thickness, 0., 0.),
+                Quat::IDENTITY,
+                side,
+            ),
+            (
+                Vec3::new(0., 0., -half_height - half_thickness),
+                Quat::IDENTITY,
+                end.clone(),
+            ),
+            (
+                Vec3::new(0., 0., half_height + half_thickness),
+                Quat::IDENTITY,
+                end,
+            ),
+        ])
+    }
 }
 
 impl Default for Table {
@@ -118,12 +169,15 @@ impl From<Table> for Mesh {
             indices.push(i);
         }
 
-        Mesh::new(
+        let mesh = Mesh::new(
             PrimitiveTopology::TriangleList,
             RenderAssetUsages::default(),
         )
         .with_inserted_indices(Indices::U32(indices))
         .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
-        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        // No top, and walls without thickness
+        debug_check("table", || validate_mesh(&mesh, Surface::Open));
+        mesh
     }
 }
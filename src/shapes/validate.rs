@@ -0,0 +1,177 @@
+//! Checks of the generated meshes, whose vertices, normals and winding are
+//! laid out by hand: a mistake only shows as an invisible face or a bad
+//! collider.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use thiserror::Error;
+
+use super::MeshElements;
+
+/// Distance under which two vertices are the same point.
+const WELD_DISTANCE: f32 = 1e-5;
+/// Error allowed on the length of a normal.
+const NORMAL_TOLERANCE: f32 = 1e-3;
+/// Area under which a triangle is degenerate.
+const MIN_AREA: f32 = 1e-9;
+
+#[derive(Clone, Debug, PartialEq, Error)]
+pub(crate) enum MeshError {
+    #[error("Only triangle lists are supported")]
+    Topology,
+    #[error("No {0} attribute")]
+    MissingAttribute(&'static str),
+    #[error("{vertices} vertices but {normals} normals")]
+    NormalCount { vertices: usize, normals: usize },
+    #[error("{0} indices aren't whole triangles")]
+    IndexCount(usize),
+    #[error("Index {index} out of {vertices} vertices")]
+    IndexOutOfBounds { index: u32, vertices: usize },
+    #[error("Vertex {0} isn't finite")]
+    NotFinite(usize),
+    #[error("Normal of vertex {vertex} has a length of {length}")]
+    NormalLength { vertex: usize, length: f32 },
+    #[error("Triangle {0} is degenerate")]
+    Degenerate(usize),
+    #[error("Triangle {0} is wound against its normals")]
+    Winding(usize),
+    #[error("Edge from {from} to {to} has {faces} faces instead of 2")]
+    Open { from: Vec3, to: Vec3, faces: usize },
+    #[error("Edge from {from} to {to} has the same direction in both its faces")]
+    Orientation { from: Vec3, to: Vec3 },
+}
+
+/// What the surface of a mesh must be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Surface {
+    /// Any surface
+    Open,
+    /// Watertight: every edge is shared by two faces, wound the same way
+    Closed,
+}
+
+impl MeshElements {
+    pub(crate) fn validate(&self, surface: Surface) -> Result<(), MeshError> {
+        validate(&self.vertices, &self.normals, &self.indices, surface)
+    }
+}
+
+/// Check a triangle list mesh with positions and normals.
+pub(crate) fn validate_mesh(mesh: &Mesh, surface: Surface) -> Result<(), MeshError> {
+    MeshElements::try_from(mesh)?.validate(surface)
+}
+
+/// Log what is wrong with a generated mesh, in debug builds only.
+pub(crate) fn debug_check(name: &str, validation: impl FnOnce() -> Result<(), MeshError>) {
+    if cfg!(debug_assertions) {
+        if let Err(error) = validation() {
+            warn!("Invalid {name} mesh: {error}");
+        }
+    }
+}
+
+fn validate(
+    vertices: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    indices: &[u32],
+    surface: Surface,
+) -> Result<(), MeshError> {
+    if vertices.len() != normals.len() {
+        return Err(MeshError::NormalCount {
+            vertices: vertices.len(),
+            normals: normals.len(),
+        });
+    }
+    if !indices.chunks_exact(3).remainder().is_empty() {
+        return Err(MeshError::IndexCount(indices.len()));
+    }
+    if let Some(index) = indices
+        .iter()
+        .find(|index| **index as usize >= vertices.len())
+    {
+        return Err(MeshError::IndexOutOfBounds {
+            index: *index,
+            vertices: vertices.len(),
+        });
+    }
+
+    let vertices: Vec<Vec3> = vertices.iter().copied().map(Vec3::from).collect();
+    let normals: Vec<Vec3> = normals.iter().copied().map(Vec3::from).collect();
+    if let Some(vertex) = vertices.iter().position(|vertex| !vertex.is_finite()) {
+        return Err(MeshError::NotFinite(vertex));
+    }
+    for (vertex, normal) in normals.iter().enumerate() {
+        let length = normal.length();
+        if !((1. - NORMAL_TOLERANCE)..=(1. + NORMAL_TOLERANCE)).contains(&length) {
+            return Err(MeshError::NormalLength { vertex, length });
+        }
+    }
+
+    for (triangle, corners) in indices.chunks_exact(3).enumerate() {
+        let [a, b, c] = [0, 1, 2].map(|corner| corners[corner] as usize);
+        // Counter clockwise seen from the front
+        let face = (vertices[b] - vertices[a]).cross(vertices[c] - vertices[a]);
+        if face.length() / 2. <= MIN_AREA {
+            return Err(MeshError::Degenerate(triangle));
+        }
+        if face.dot(normals[a] + normals[b] + normals[c]) <= 0. {
+            return Err(MeshError::Winding(triangle));
+        }
+    }
+
+    if surface == Surface::Closed {
+        closed(&vertices, indices)?;
+    }
+    Ok(())
+}
+
+/// Check that every edge has two faces going through it in opposite
+/// directions. Faces don't share the vertices of their corners when their
+/// normals differ, so the vertices at the same place are merged first.
+fn closed(vertices: &[Vec3], indices: &[u32]) -> Result<(), MeshError> {
+    // Few vertices in a generated element, comparing them all is enough
+    let mut points: Vec<Vec3> = Vec::new();
+    let welded: Vec<usize> = vertices
+        .iter()
+        .map(|vertex| {
+            points
+                .iter()
+                .position(|point| point.distance(*vertex) <= WELD_DISTANCE)
+                .unwrap_or_else(|| {
+                    points.push(*vertex);
+                    points.len() - 1
+                })
+        })
+        .collect();
+
+    // Faces going from the lowest point to the highest, and backward
+    let mut edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for corners in indices.chunks_exact(3) {
+        for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+            let from = welded[corners[from] as usize];
+            let to = welded[corners[to] as usize];
+            let faces = edges.entry((from.min(to), from.max(to))).or_default();
+            if from < to {
+                faces.0 += 1;
+            } else {
+                faces.1 += 1;
+            }
+        }
+    }
+
+    for ((from, to), (forward, backward)) in edges {
+        let (from, to) = (points[from], points[to]);
+        match (forward, backward) {
+            (1, 1) => {}
+            (2, 0) | (0, 2) => return Err(MeshError::Orientation { from, to }),
+            _ => {
+                return Err(MeshError::Open {
+                    from,
+                    to,
+                    faces: forward + backward,
+                })
+            }
+        }
+    }
+    Ok(())
+}
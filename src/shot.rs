@@ -0,0 +1,190 @@
+//! Shots are ordered sequences of switches activated within a time
+//! window, e.g. the entry then the exit of an orbit.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use thiserror::Error;
+
+use crate::element::Side;
+use crate::game::{BallDrained, ServeBall};
+use crate::physics::StepSet;
+use crate::switch::SwitchEvent;
+use crate::Ball;
+
+#[derive(Debug, Error)]
+pub(crate) enum ShotError {
+    #[error("Shot {0} has no switches")]
+    NoSwitches(String),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Shot {
+    pub(crate) name: String,
+    /// Switches the ball must activate, in order
+    pub(crate) switches: Vec<String>,
+    /// Maximum time in seconds between the first and the last switch
+    pub(crate) window: f32,
+}
+
+/// Sent when a shot is completed.
+#[derive(Clone, Debug, Event)]
+pub(crate) struct ShotEvent {
+    pub(crate) name: String,
+    /// Number of consecutive shots without the ball touching
+    /// a flipper, starting at 1
+    pub(crate) combo: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ShotProgress {
+    /// Number of switches of the shot already activated
+    count: usize,
+    /// Time at which the first switch was activated
+    start: f32,
+}
+
+/// Shots of the table and their progress.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct Shots {
+    shots: Vec<(Shot, ShotProgress)>,
+    combo: u32,
+}
+
+impl Shots {
+    pub(crate) fn new(shots: Vec<Shot>) -> Result<Self, ShotError> {
+        if let Some(shot) = shots.iter().find(|shot| shot.switches.is_empty()) {
+            return Err(ShotError::NoSwitches(shot.name.clone()));
+        }
+
+        Ok(Self {
+            shots: shots
+                .into_iter()
+                .map(|shot| (shot, ShotProgress::default()))
+                .collect(),
+            combo: 0,
+        })
+    }
+
+    /// Record the activation of `switch` at time `now` and return
+    /// the names of the completed shots.
+    pub(crate) fn activate(&mut self, switch: &str, now: f32) -> Vec<String> {
+        let mut completed = Vec::new();
+        for (shot, progress) in &mut self.shots {
+            if progress.count > 0 && now - progress.start > shot.window {
+                progress.count = 0;
+            }
+
+            if shot.switches[progress.count] == switch {
+                if progress.count == 0 {
+                    progress.start = now;
+                }
+                progress.count += 1;
+            } else if shot.switches[0] == switch {
+                // The shot starts again
+                progress.count = 1;
+                progress.start = now;
+            }
+
+            if progress.count == shot.switches.len() {
+                progress.count = 0;
+                completed.push(shot.name.clone());
+            }
+        }
+
+        completed
+    }
+
+    fn reset_combo(&mut self) {
+        self.combo = 0;
+    }
+
+    /// Forget the shots in progress and the combo.
+    fn reset(&mut self) {
+        for (_, progress) in &mut self.shots {
+            *progress = ShotProgress::default();
+        }
+        self.reset_combo();
+    }
+}
+
+pub(crate) struct ShotPlugin;
+
+impl Plugin for ShotPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Shots::new(table_shots()).unwrap())
+            .add_event::<ShotEvent>()
+            .add_systems(
+                FixedUpdate,
+                (reset_shots, detect_shots, break_combo)
+                    .chain()
+                    .in_set(StepSet::Shots),
+            );
+    }
+}
+
+/// Shots of the table.
+fn table_shots() -> Vec<Shot> {
+    vec![
+        Shot {
+            name: "full_plunge".to_string(),
+            switches: vec!["launch".to_string(), "left_lane".to_string()],
+            window: 1.5,
+        },
+        Shot {
+            name: "top_lanes".to_string(),
+            switches: vec!["left_lane".to_string(), "right_lane".to_string()],
+            window: 1.5,
+        },
+    ]
+}
+
+/// Each ball, and so each game, starts with no shot in progress.
+fn reset_shots(mut shots: ResMut<Shots>, mut serve: EventReader<ServeBall>) {
+    if serve.read().count() > 0 {
+        shots.reset();
+    }
+}
+
+fn detect_shots(
+    time: Res<Time>,
+    mut shots: ResMut<Shots>,
+    mut switch_events: EventReader<SwitchEvent>,
+    mut shot_events: EventWriter<ShotEvent>,
+) {
+    for event in switch_events.read() {
+        if !event.active {
+            continue;
+        }
+        for name in shots.activate(&event.name, time.elapsed_seconds()) {
+            shots.combo += 1;
+            debug!("Shot {name} (combo {})", shots.combo);
+            shot_events.send(ShotEvent {
+                name,
+                combo: shots.combo,
+            });
+        }
+    }
+}
+
+/// Combos end when the ball touches a flipper or drains.
+fn break_combo(
+    mut shots: ResMut<Shots>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut drained: EventReader<BallDrained>,
+    flippers: Query<(), With<Side>>,
+    balls: Query<(), With<Ball>>,
+) {
+    if drained.read().count() > 0 {
+        shots.reset_combo();
+    }
+
+    for event in collision_events.read() {
+        if let CollisionEvent::Started(first, second, _) = *event {
+            if (flippers.contains(first) && balls.contains(second))
+                || (flippers.contains(second) && balls.contains(first))
+            {
+                shots.reset_combo();
+            }
+        }
+    }
+}
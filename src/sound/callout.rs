@@ -0,0 +1,86 @@
+//! Voice callouts, spoken one at a time by priority.
+
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+
+use super::level;
+use crate::operator::Settings;
+
+/// Time in seconds a callout waits before being dropped.
+const MAX_WAIT: f32 = 3.;
+/// Time in seconds after which a callout is stopped, in case its sound
+/// can't be played.
+const MAX_LENGTH: f32 = 5.;
+
+/// Speak `voice/<name>.ogg` from the assets. Callouts with a higher
+/// priority are spoken first.
+#[derive(Clone, Debug, Event)]
+pub(crate) struct Callout {
+    pub(crate) name: String,
+    pub(crate) priority: i32,
+}
+
+/// Callout being spoken.
+#[derive(Component)]
+pub(crate) struct Voice {
+    started: f32,
+}
+
+/// Callouts waiting, with the time they were sent.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct Callouts(Vec<(Callout, f32)>);
+
+pub(crate) fn queue_callouts(
+    time: Res<Time>,
+    mut callouts: ResMut<Callouts>,
+    mut events: EventReader<Callout>,
+) {
+    let now = time.elapsed_seconds();
+    callouts
+        .0
+        .extend(events.read().map(|callout| (callout.clone(), now)));
+}
+
+pub(crate) fn speak(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    mut callouts: ResMut<Callouts>,
+    voices: Query<(Entity, &Voice)>,
+) {
+    let now = time.elapsed_seconds();
+    if let Ok((entity, voice)) = voices.get_single() {
+        if now - voice.started < MAX_LENGTH {
+            return;
+        }
+        commands.entity(entity).despawn();
+    }
+
+    callouts.0.retain(|(_, sent)| now - sent < MAX_WAIT);
+    // The first sent among the highest priority
+    let Some(index) = callouts
+        .0
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, (callout, _))| callout.priority)
+        .map(|(index, _)| index)
+    else {
+        return;
+    };
+    let (callout, _) = callouts.0.remove(index);
+
+    debug!("Callout {}", callout.name);
+    commands.spawn((
+        AudioBundle {
+            source: asset_server.load(format!("voice/{}.ogg", callout.name)),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(level(settings.voice_volume)),
+                ..default()
+            },
+        },
+        Voice { started: now },
+    ));
+}
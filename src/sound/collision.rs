@@ -0,0 +1,190 @@
+//! Hits of the ball on the table elements and rumble of the rolling ball.
+//!
+//! `sounds/hit_<material>.ogg` and `sounds/rolling.ogg` are played when
+//! they are in the assets, synthesized sounds otherwise.
+
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier3d::prelude::*;
+
+use super::{level, Synth};
+use crate::element::PinballMaterial;
+use crate::operator::Settings;
+use crate::Ball;
+
+/// Impact speed in m/s under which nothing is heard.
+const MIN_SPEED: f32 = 0.05;
+/// Impact speed of the loudest hit.
+const MAX_SPEED: f32 = 2.;
+/// Time in seconds before the same pair of colliders sounds again.
+const HIT_COOLDOWN: f32 = 0.08;
+/// Speed in m/s of the loudest rumble.
+const MAX_ROLLING_SPEED: f32 = 1.;
+const ROLLING_VOLUME: f32 = 0.4;
+
+/// Recorded or synthesized sound.
+#[derive(Clone, Debug)]
+enum Sample {
+    Recorded(Handle<AudioSource>),
+    Synthesized(Handle<Synth>),
+}
+
+impl Sample {
+    fn new(
+        path: String,
+        synth: impl FnOnce() -> Synth,
+        asset_server: &AssetServer,
+        synths: &mut Assets<Synth>,
+    ) -> Self {
+        if asset_exists(&path) {
+            debug!("Recorded sound {path}");
+            Sample::Recorded(asset_server.load(path))
+        } else {
+            Sample::Synthesized(synths.add(synth()))
+        }
+    }
+
+    fn spawn(&self, commands: &mut Commands, settings: PlaybackSettings) -> Entity {
+        match self {
+            Sample::Recorded(source) => commands.spawn(AudioBundle {
+                source: source.clone(),
+                settings,
+            }),
+            Sample::Synthesized(source) => commands.spawn(AudioSourceBundle {
+                source: source.clone(),
+                settings,
+            }),
+        }
+        .id()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn asset_exists(path: &str) -> bool {
+    bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join("assets")
+        .join(path)
+        .exists()
+}
+
+/// The assets can't be listed on the web.
+#[cfg(target_arch = "wasm32")]
+fn asset_exists(_path: &str) -> bool {
+    false
+}
+
+#[derive(Resource)]
+pub(crate) struct CollisionSounds {
+    hits: HashMap<PinballMaterial, Sample>,
+}
+
+/// Looping sound following the speed of the balls.
+#[derive(Component)]
+pub(crate) struct RollingSound;
+
+pub(crate) fn setup_collision_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut synths: ResMut<Assets<Synth>>,
+) {
+    let hits = PinballMaterial::ALL
+        .into_iter()
+        .map(|material| {
+            let path = format!("sounds/hit_{}.ogg", material.name());
+            let sample = Sample::new(path, || Synth::hit(material), &asset_server, &mut synths);
+            (material, sample)
+        })
+        .collect();
+    commands.insert_resource(CollisionSounds { hits });
+
+    let rolling = Sample::new(
+        "sounds/rolling.ogg".to_string(),
+        Synth::rolling,
+        &asset_server,
+        &mut synths,
+    );
+    let rolling = rolling.spawn(
+        &mut commands,
+        PlaybackSettings {
+            mode: PlaybackMode::Loop,
+            volume: Volume::ZERO,
+            ..default()
+        },
+    );
+    commands.entity(rolling).insert(RollingSound);
+}
+
+/// Loudness between 0 and 1 of a hit at `speed`.
+fn hit_volume(speed: f32) -> f32 {
+    ((speed - MIN_SPEED) / (MAX_SPEED - MIN_SPEED)).clamp(0., 1.)
+}
+
+pub(crate) fn play_hits(
+    mut commands: Commands,
+    mut contacts: EventReader<ContactForceEvent>,
+    mut last_hits: Local<HashMap<(Entity, Entity), f32>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    sounds: Res<CollisionSounds>,
+    balls: Query<&Velocity, With<Ball>>,
+    materials: Query<&PinballMaterial>,
+) {
+    let now = time.elapsed_seconds();
+    last_hits.retain(|_, last| now - *last < HIT_COOLDOWN);
+
+    for contact in contacts.read() {
+        let (velocity, material) = match (
+            balls.get(contact.collider1),
+            materials.get(contact.collider2),
+        ) {
+            (Ok(velocity), Ok(material)) => (velocity, material),
+            _ => match (
+                balls.get(contact.collider2),
+                materials.get(contact.collider1),
+            ) {
+                (Ok(velocity), Ok(material)) => (velocity, material),
+                _ => continue,
+            },
+        };
+
+        // Only the speed along the contact normal makes noise
+        let speed = velocity.linvel.dot(contact.max_force_direction).abs();
+        let volume = hit_volume(speed);
+        let pair = (contact.collider1, contact.collider2);
+        if volume <= 0. || last_hits.contains_key(&pair) {
+            continue;
+        }
+        last_hits.insert(pair, now);
+
+        if let Some(sample) = sounds.hits.get(material) {
+            sample.spawn(
+                &mut commands,
+                PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::new(volume * level(settings.effects_volume)),
+                    // Harder hits sound higher
+                    speed: 0.85 + 0.3 * volume,
+                    ..default()
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn roll(
+    settings: Res<Settings>,
+    balls: Query<&Velocity, With<Ball>>,
+    rolling: Query<&AudioSink, With<RollingSound>>,
+) {
+    let Ok(sink) = rolling.get_single() else {
+        return;
+    };
+    let speed = balls
+        .iter()
+        .map(|velocity| velocity.linvel.length())
+        .fold(0., f32::max);
+    let ratio = (speed / MAX_ROLLING_SPEED).min(1.);
+    sink.set_volume(ratio * ROLLING_VOLUME * level(settings.effects_volume));
+    sink.set_speed(0.7 + 0.6 * ratio);
+}
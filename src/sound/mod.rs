@@ -0,0 +1,69 @@
+//! Sounds played by name from the rules, sounds of the ball, music of
+//! the modes and voice callouts.
+
+use bevy::audio::{AddAudioSource, PlaybackMode, Volume};
+use bevy::prelude::*;
+
+pub(crate) use callout::*;
+pub(crate) use collision::*;
+pub(crate) use music::*;
+pub(crate) use synth::*;
+
+use crate::operator::Settings;
+
+mod callout;
+mod collision;
+mod music;
+mod synth;
+
+/// Highest volume level of the settings.
+pub(crate) const MAX_VOLUME: u32 = 10;
+
+/// Play `sounds/<name>.ogg` from the assets.
+#[derive(Clone, Debug, Event)]
+pub(crate) struct PlaySound(pub(crate) String);
+
+pub(crate) struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Synth>()
+            .add_event::<PlaySound>()
+            .add_event::<Callout>()
+            .init_resource::<Callouts>()
+            .add_systems(Startup, setup_collision_sounds)
+            .add_systems(
+                Update,
+                (
+                    play_sounds,
+                    play_hits,
+                    roll,
+                    (queue_callouts, speak).chain(),
+                    (change_music, fade_music).chain(),
+                ),
+            );
+    }
+}
+
+/// Volume between 0 and 1 of a volume level of the settings.
+fn level(value: u32) -> f32 {
+    value.min(MAX_VOLUME) as f32 / MAX_VOLUME as f32
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    mut sounds: EventReader<PlaySound>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+) {
+    for PlaySound(name) in sounds.read() {
+        commands.spawn(AudioBundle {
+            source: asset_server.load(format!("sounds/{name}.ogg")),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(level(settings.effects_volume)),
+                ..default()
+            },
+        });
+    }
+}
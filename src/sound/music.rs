@@ -0,0 +1,103 @@
+//! Music of the running mode, crossfaded when the mode changes and
+//! lowered while a callout is spoken.
+
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+
+use super::{level, Voice};
+use crate::game::GameState;
+use crate::operator::Settings;
+use crate::rules::Rules;
+
+/// Time in seconds of a crossfade between two tracks.
+const CROSSFADE: f32 = 1.5;
+/// Volume of the music while a callout is spoken.
+const DUCKING: f32 = 0.3;
+/// Time in seconds to lower or raise the music for a callout.
+const DUCKING_TIME: f32 = 0.2;
+
+/// Playing music, fading in or out.
+#[derive(Component)]
+pub(crate) struct Track {
+    name: String,
+    /// Fading out once replaced
+    playing: bool,
+    /// Between 0 and 1
+    gain: f32,
+}
+
+pub(crate) fn change_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    state: Res<State<GameState>>,
+    rules: Res<Rules>,
+    mut tracks: Query<&mut Track>,
+) {
+    let music = match state.get() {
+        GameState::Playing => rules.music(),
+        _ => None,
+    };
+    if tracks
+        .iter()
+        .any(|track| track.playing && Some(track.name.as_str()) == music)
+    {
+        return;
+    }
+
+    for mut track in &mut tracks {
+        track.playing = false;
+    }
+    if let Some(name) = music {
+        debug!("Music {name}");
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(format!("music/{name}.ogg")),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: Volume::ZERO,
+                    ..default()
+                },
+            },
+            Track {
+                name: name.to_string(),
+                playing: true,
+                gain: 0.,
+            },
+        ));
+    }
+}
+
+pub(crate) fn fade_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut ducking: Local<Option<f32>>,
+    voices: Query<(), With<Voice>>,
+    mut tracks: Query<(Entity, &mut Track, Option<&AudioSink>)>,
+) {
+    let delta = time.delta_seconds();
+    let target = if voices.is_empty() { 1. } else { DUCKING };
+    let ducking = ducking.get_or_insert(1.);
+    let step = (1. - DUCKING) * delta / DUCKING_TIME;
+    *ducking = if *ducking < target {
+        (*ducking + step).min(target)
+    } else {
+        (*ducking - step).max(target)
+    };
+
+    for (entity, mut track, sink) in &mut tracks {
+        let step = delta / CROSSFADE;
+        track.gain = if track.playing {
+            (track.gain + step).min(1.)
+        } else {
+            (track.gain - step).max(0.)
+        };
+        if !track.playing && track.gain <= 0. {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(sink) = sink {
+            sink.set_volume(track.gain * *ducking * level(settings.music_volume));
+        }
+    }
+}
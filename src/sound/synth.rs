@@ -0,0 +1,130 @@
+//! Sounds synthesized at startup, used when no recorded sound is in the
+//! assets.
+
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{Decodable, Source};
+use bevy::prelude::*;
+
+use crate::element::PinballMaterial;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Mono sound kept in memory.
+#[derive(Asset, Clone, Debug, TypePath)]
+pub(crate) struct Synth {
+    samples: Arc<[f32]>,
+}
+
+impl Synth {
+    /// Sound of the ball hitting `material`.
+    pub(crate) fn hit(material: PinballMaterial) -> Self {
+        // Partials in Hz, decay time in seconds and amount of noise
+        let (partials, decay, noise): (&[f32], f32, f32) = match material {
+            PinballMaterial::Metal => (&[1800., 2750., 4100.], 0.25, 0.05),
+            PinballMaterial::Rubber => (&[180., 260.], 0.04, 0.2),
+            PinballMaterial::Wood => (&[420., 950.], 0.06, 0.4),
+            PinballMaterial::Plastic => (&[1100., 1700.], 0.03, 0.3),
+            PinballMaterial::Glass => (&[2300., 3400.], 0.12, 0.1),
+        };
+
+        let mut rng = Noise::default();
+        let length = (decay * 5. * SAMPLE_RATE as f32) as usize;
+        let samples = (0..length)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let tone = partials
+                    .iter()
+                    .enumerate()
+                    .map(|(n, frequency)| (TAU * frequency * t).sin() / (n + 1) as f32)
+                    .sum::<f32>()
+                    / partials.len() as f32;
+                let sample = tone * (1. - noise) + rng.sample() * noise;
+                sample * (-t / decay).exp()
+            })
+            .collect();
+        Self { samples }
+    }
+
+    /// Rumble of a rolling ball, made to be looped.
+    pub(crate) fn rolling() -> Self {
+        let mut rng = Noise::default();
+        let mut low = 0.;
+        let samples = (0..SAMPLE_RATE)
+            .map(|_| {
+                // One pole low-pass filter
+                low += (rng.sample() - low) * 0.02;
+                low * 4.
+            })
+            .collect();
+        Self { samples }
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+pub(crate) struct SynthDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+/// Xorshift white noise, the same on every run.
+struct Noise(u32);
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self(0x9E37_79B9)
+    }
+}
+
+impl Noise {
+    /// Between -1 and 1
+    fn sample(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2. - 1.
+    }
+}
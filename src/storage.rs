@@ -0,0 +1,112 @@
+//! Persistence of small values (high scores, settings...).
+//!
+//! Values are saved as RON in the user data directory, or in the
+//! browser local storage when running as WebAssembly.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Values are neither loaded nor saved while set, e.g. during a simulation:
+/// it must not depend on the machine it runs on, nor change it.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Error)]
+pub(crate) enum StorageError {
+    #[error("No storage available")]
+    Unavailable,
+    #[error("Could not access storage: {0}")]
+    Io(String),
+    #[error("Could not serialize value: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("Could not deserialize value: {0}")]
+    Deserialize(#[from] ron::error::SpannedError),
+}
+
+/// Load the value saved under `key`, `None` if nothing was saved yet.
+pub(crate) fn load<T: DeserializeOwned>(key: &str) -> Result<Option<T>, StorageError> {
+    if is_read_only() {
+        return Ok(None);
+    }
+    match read(key)? {
+        Some(content) => Ok(Some(ron::from_str(&content)?)),
+        None => Ok(None),
+    }
+}
+
+/// Load the value saved under `key`, the default value if nothing
+/// was saved yet or it could not be loaded.
+pub(crate) fn load_or_default<T: DeserializeOwned + Default>(key: &str) -> T {
+    match load(key) {
+        Ok(value) => value.unwrap_or_default(),
+        Err(error) => {
+            bevy::log::error!("Could not load {key}: {error}");
+            T::default()
+        }
+    }
+}
+
+pub(crate) fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+pub(crate) fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+pub(crate) fn save<T: Serialize>(key: &str, value: &T) -> Result<(), StorageError> {
+    if is_read_only() {
+        return Ok(());
+    }
+    let content = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+    write(key, &content)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> Result<std::path::PathBuf, StorageError> {
+    let directory = dirs::data_dir()
+        .ok_or(StorageError::Unavailable)?
+        .join("pinball");
+    Ok(directory.join(format!("{key}.ron")))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(key: &str) -> Result<Option<String>, StorageError> {
+    match std::fs::read_to_string(path(key)?) {
+        Ok(content) => Ok(Some(content)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(StorageError::Io(error.to_string())),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(key: &str, content: &str) -> Result<(), StorageError> {
+    let path = path(key)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| StorageError::Io(error.to_string()))?;
+    }
+    std::fs::write(path, content).map_err(|error| StorageError::Io(error.to_string()))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, StorageError> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or(StorageError::Unavailable)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(key: &str) -> Result<Option<String>, StorageError> {
+    local_storage()?
+        .get_item(&format!("pinball.{key}"))
+        .map_err(|error| StorageError::Io(format!("{error:?}")))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(key: &str, content: &str) -> Result<(), StorageError> {
+    local_storage()?
+        .set_item(&format!("pinball.{key}"), content)
+        .map_err(|error| StorageError::Io(format!("{error:?}")))
+}
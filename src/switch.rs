@@ -0,0 +1,56 @@
+//! Switches are sensors reporting the ball passing over them.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::physics::StepSet;
+use crate::Ball;
+
+/// Sensor reporting to the rules, identified by its name.
+#[derive(Clone, Debug, Component)]
+pub(crate) struct Switch(pub(crate) String);
+
+/// Sent when a ball enters (`active`) or leaves a switch.
+#[derive(Clone, Debug, Event)]
+pub(crate) struct SwitchEvent {
+    pub(crate) name: String,
+    pub(crate) active: bool,
+}
+
+pub(crate) struct SwitchPlugin;
+
+impl Plugin for SwitchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SwitchEvent>()
+            .add_systems(FixedUpdate, detect_switches.in_set(StepSet::Switches));
+    }
+}
+
+fn detect_switches(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut switch_events: EventWriter<SwitchEvent>,
+    switches: Query<&Switch>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in collision_events.read() {
+        let (first, second, active) = match *event {
+            CollisionEvent::Started(first, second, _) => (first, second, true),
+            CollisionEvent::Stopped(first, second, _) => (first, second, false),
+        };
+        let switch = if balls.contains(first) {
+            switches.get(second)
+        } else if balls.contains(second) {
+            switches.get(first)
+        } else {
+            continue;
+        };
+
+        if let Ok(Switch(name)) = switch {
+            debug!("Switch {name} active: {active}");
+            switch_events.send(SwitchEvent {
+                name: name.clone(),
+                active,
+            });
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Tests of the saucers: a ball settling in one is held, then ejected.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::Playground;
+use crate::kicker::Saucer;
+use crate::{BALL_RADIUS, WALL_HEIGHT};
+
+/// Rounding of the positions going through the physics.
+const TOLERANCE: f32 = 0.0001;
+
+#[test]
+fn saucer_holds_and_ejects_ball() {
+    let mut playground = Playground::new();
+    let world = playground.app.world_mut();
+    let (saucer, position) = world.query::<(&Saucer, &Transform)>().single(world);
+    let (saucer, position) = (saucer.clone(), position.translation);
+    let ball = playground.ball(
+        Vec3::new(position.x, -WALL_HEIGHT / 2. + BALL_RADIUS, position.z),
+        Vec3::ZERO,
+    );
+
+    // Captured as soon as the ball is found over it
+    let mut captured = None;
+    for step in 1..=Playground::steps(0.2) {
+        playground.step();
+        if let Some(event) = playground.switch_events().first() {
+            assert_eq!(event.name, saucer.name);
+            assert!(event.active, "switch off before the capture");
+            captured = Some(step);
+            break;
+        }
+    }
+    let captured = captured.expect("ball not captured by the saucer");
+    let held = playground.position(ball);
+    assert!(
+        held.xz().abs_diff_eq(position.xz(), TOLERANCE),
+        "ball held off the saucer: {held}"
+    );
+
+    // Held out of the simulation until ejected
+    let mut ejected = None;
+    for step in captured + 1..=captured + Playground::steps(saucer.hold + 0.5) {
+        playground.step();
+        let events = playground.switch_events();
+        if let Some(event) = events.first() {
+            assert_eq!(event.name, saucer.name);
+            assert!(!event.active, "ball captured again while held");
+            ejected = Some(step);
+            break;
+        }
+        let moved = playground.position(ball);
+        assert!(
+            moved.abs_diff_eq(held, TOLERANCE),
+            "held ball moved to {moved}"
+        );
+        assert_eq!(
+            playground.app.world().get::<RigidBody>(ball),
+            Some(&RigidBody::KinematicPositionBased)
+        );
+    }
+    let ejected = ejected.expect("ball not ejected from the saucer");
+    assert_eq!(
+        playground.app.world().get::<RigidBody>(ball),
+        Some(&RigidBody::Dynamic)
+    );
+    let hold = Playground::seconds(ejected - captured);
+    assert!(
+        (hold - saucer.hold).abs() <= Playground::seconds(1),
+        "ball held {hold:.3} s instead of {} s",
+        saucer.hold
+    );
+
+    // Kicked out, without being captured again on its way
+    for _ in 0..Playground::steps(0.5) {
+        playground.step();
+        assert!(
+            playground.switch_events().is_empty(),
+            "ball captured again after its ejection"
+        );
+    }
+    let distance = playground.position(ball).xz().distance(position.xz());
+    assert!(
+        distance > BALL_RADIUS,
+        "ejected ball still in the saucer: {}",
+        playground.position(ball)
+    );
+}
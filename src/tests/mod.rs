@@ -0,0 +1,147 @@
+//! Tests of the table running in an app without window, one physics step
+//! an update.
+
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_rapier3d::prelude::*;
+
+use crate::element::{self, Playfield, Side};
+use crate::kicker::KickerPlugin;
+use crate::physics::{PhysicsPlugin, StepSet};
+use crate::replay::Button;
+use crate::switch::SwitchEvent;
+use crate::tilt::Tilt;
+use crate::PHYSICS_HZ;
+
+mod kicker;
+mod physics;
+mod replay;
+mod shapes;
+mod tilt;
+
+/// Table and its physics, with its kickers and saucers but without the
+/// rules: the balls are put on it by the tests.
+struct Playground {
+    app: App,
+}
+
+impl Playground {
+    fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            bevy::input::InputPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            StatesPlugin,
+        ))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .add_plugins((PhysicsPlugin, KickerPlugin))
+        .add_event::<SwitchEvent>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1. / PHYSICS_HZ,
+        )))
+        .init_resource::<Tilt>()
+        .init_resource::<ButtonInput<Button>>()
+        .add_systems(Startup, crate::setup)
+        .add_systems(FixedUpdate, crate::flip.in_set(StepSet::Controls));
+        app.finish();
+        app.cleanup();
+        // Startup
+        app.update();
+
+        Self { app }
+    }
+
+    /// Put a ball at `position` on the table going at `velocity`, both in
+    /// the coordinates of the table.
+    fn ball(&mut self, position: Vec3, velocity: Vec3) -> Entity {
+        let world = self.app.world_mut();
+        let table = world.resource::<Playfield>().0;
+        let ball = world.run_system_once_with(
+            table,
+            |In(table): In<Entity>,
+             mut commands: Commands,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<StandardMaterial>>| {
+                element::ball(&mut commands, &mut meshes, &mut materials, table)
+            },
+        );
+        // The velocity of a rigid body is in the world coordinates
+        let rotation = world
+            .get::<Transform>(table)
+            .map(|transform| transform.rotation)
+            .unwrap_or_default();
+        world.entity_mut(ball).insert((
+            Transform::from_translation(position),
+            Velocity::linear(rotation * velocity),
+        ));
+        ball
+    }
+
+    /// Position of `ball` in the coordinates of the table.
+    fn position(&self, ball: Entity) -> Vec3 {
+        self.app
+            .world()
+            .get::<Transform>(ball)
+            .expect("ball")
+            .translation
+    }
+
+    /// Transform of the flipper in the coordinates of the table.
+    fn flipper(&mut self, side: Side) -> Transform {
+        let world = self.app.world_mut();
+        world
+            .query::<(&Transform, &Side)>()
+            .iter(world)
+            .find(|(_, flipper)| **flipper == side)
+            .map(|(transform, _)| *transform)
+            .expect("flipper")
+    }
+
+    fn press(&mut self, button: Button) {
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<Button>>()
+            .press(button);
+    }
+
+    fn release(&mut self, button: Button) {
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<Button>>()
+            .release(button);
+    }
+
+    /// Switch events sent since the last call.
+    fn switch_events(&mut self) -> Vec<SwitchEvent> {
+        self.app
+            .world_mut()
+            .resource_mut::<Events<SwitchEvent>>()
+            .drain()
+            .collect()
+    }
+
+    /// Run one physics step.
+    fn step(&mut self) {
+        self.app.update();
+    }
+
+    /// Time in seconds of `steps` physics steps.
+    fn seconds(steps: usize) -> f32 {
+        steps as f32 / PHYSICS_HZ as f32
+    }
+
+    /// Number of physics steps in `seconds`.
+    fn steps(seconds: f32) -> usize {
+        (seconds * PHYSICS_HZ as f32).ceil() as usize
+    }
+}
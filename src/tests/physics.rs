@@ -0,0 +1,200 @@
+//! Regression tests of the table geometry: the ball must stay between the
+//! walls, the floor and the glass, whatever it hits.
+
+use bevy::prelude::*;
+
+use super::Playground;
+use crate::element::Side;
+use crate::physics::PhysicsConfig;
+use crate::replay::Button;
+use crate::{BALL_RADIUS, FLIPPER_SWING, TABLE_HEIGHT, TABLE_WIDTH, WALL_HEIGHT};
+
+/// Penetration allowed in the colliders.
+const TOLERANCE: f32 = 0.004;
+/// Height of the center of a ball on the floor.
+const ON_FLOOR: f32 = -WALL_HEIGHT / 2. + BALL_RADIUS;
+/// Height of the center of a ball against the glass, which is 5 mm thick.
+const UNDER_GLASS: f32 = WALL_HEIGHT / 2. - 0.005 / 2. - BALL_RADIUS;
+
+/// Panic if the ball at `position` went through a wall, the floor or the
+/// glass.
+fn assert_on_table(position: Vec3, case: &str, step: usize) {
+    let time = Playground::seconds(step);
+    assert!(
+        position.is_finite(),
+        "{case}: ball lost at {time:.2} s: {position}"
+    );
+    assert!(
+        position.x.abs() <= TABLE_WIDTH / 2. - BALL_RADIUS + TOLERANCE,
+        "{case}: ball through a side wall at {time:.2} s: {position}"
+    );
+    assert!(
+        position.z.abs() <= TABLE_HEIGHT / 2. - BALL_RADIUS + TOLERANCE,
+        "{case}: ball through the top or bottom wall at {time:.2} s: {position}"
+    );
+    assert!(
+        position.y >= ON_FLOOR - TOLERANCE,
+        "{case}: ball through the floor at {time:.2} s: {position}"
+    );
+    assert!(
+        position.y <= UNDER_GLASS + TOLERANCE,
+        "{case}: ball through the glass at {time:.2} s: {position}"
+    );
+}
+
+#[test]
+fn ball_stays_on_table() {
+    // Up to the speed of a launched ball
+    for (case, position, velocity) in [
+        (
+            "left wall",
+            Vec3::new(0., ON_FLOOR, 0.),
+            Vec3::new(-5., 0., 0.),
+        ),
+        (
+            "bottom wall",
+            Vec3::new(0.1, ON_FLOOR, 0.2),
+            Vec3::new(0., 0., 5.),
+        ),
+        (
+            "top wall",
+            Vec3::new(0., ON_FLOOR, -0.2),
+            Vec3::new(0., 0., -5.),
+        ),
+        (
+            "launch lane",
+            Vec3::new(0., ON_FLOOR, 0.1),
+            Vec3::new(5., 0., 0.),
+        ),
+        (
+            "glass",
+            Vec3::new(0., ON_FLOOR, 0.),
+            Vec3::new(0.5, 5., -1.),
+        ),
+        (
+            "floor",
+            Vec3::new(0., UNDER_GLASS, 0.),
+            Vec3::new(-0.5, -5., 1.),
+        ),
+        (
+            "flipper",
+            Vec3::new(-0.1, ON_FLOOR, -0.1),
+            Vec3::new(-1.5, 0., 4.),
+        ),
+        (
+            "top corner",
+            Vec3::new(0., ON_FLOOR, 0.),
+            Vec3::new(-3.5, 0., -3.5),
+        ),
+    ] {
+        let mut playground = Playground::new();
+        let ball = playground.ball(position, velocity);
+        for step in 0..Playground::steps(3.) {
+            playground.step();
+            assert_on_table(playground.position(ball), case, step);
+        }
+    }
+}
+
+#[test]
+fn ball_reaches_flipper_zone() {
+    const MAX_TIME: f32 = 5.;
+
+    for x in [-0.05, 0., 0.1] {
+        let mut playground = Playground::new();
+        let flipper = playground.flipper(Side::Left).translation;
+        let ball = playground.ball(Vec3::new(x, ON_FLOOR, -TABLE_HEIGHT / 2. + 0.2), Vec3::ZERO);
+        let reached = (0..Playground::steps(MAX_TIME)).any(|step| {
+            playground.step();
+            let position = playground.position(ball);
+            assert_on_table(position, "drop", step);
+            position.z >= flipper.z
+        });
+        assert!(
+            reached,
+            "ball dropped at x = {x} not down to the flipper in {MAX_TIME} s: {}",
+            playground.position(ball)
+        );
+    }
+}
+
+/// Steps for the left flipper to swing from where it is to `swing`
+/// radians away from `rest`, `None` if it does not get there in a second.
+fn swing_steps(playground: &mut Playground, rest: Quat, swing: f32) -> Option<usize> {
+    (1..=Playground::steps(1.)).find(|_| {
+        playground.step();
+        let angle = rest.angle_between(playground.flipper(Side::Left).rotation);
+        (angle - swing).abs() < 0.05
+    })
+}
+
+#[test]
+fn flipper_swings_in_time() {
+    let config = PhysicsConfig::default();
+    let mut playground = Playground::new();
+    // Let the mass of the flipper be computed
+    for _ in 0..Playground::steps(0.1) {
+        playground.step();
+    }
+    let rest = playground.flipper(Side::Left).rotation;
+
+    playground.press(Button::LeftFlipper);
+    let up = swing_steps(&mut playground, rest, FLIPPER_SWING).expect("flipper up");
+    // The steps are coarse next to the swing
+    let step = Playground::seconds(1);
+    let time = Playground::seconds(up);
+    assert!(
+        (time - config.flip_time).abs() <= step,
+        "flipper up in {time:.3} s instead of {} s",
+        config.flip_time
+    );
+
+    // Let it rest up before its fall
+    for _ in 0..Playground::steps(0.1) {
+        playground.step();
+    }
+    playground.release(Button::LeftFlipper);
+    let down = swing_steps(&mut playground, rest, 0.).expect("flipper down");
+    let time = Playground::seconds(down);
+    assert!(
+        (time - config.return_time).abs() <= step,
+        "flipper down in {time:.3} s instead of {} s",
+        config.return_time
+    );
+}
+
+#[test]
+fn flipper_lifts_cradled_ball() {
+    const MAX_TIME: f32 = 1.;
+    // Distance up the table the ball must be sent to
+    const LIFT: f32 = 0.1;
+
+    let mut playground = Playground::new();
+    let flipper = playground.flipper(Side::Left);
+    // Halfway along the flipper at rest, on its upper side
+    let along = flipper.rotation * Vec3::X;
+    let up_table = Vec3::new(along.z, 0., -along.x);
+    let mut position =
+        flipper.translation + along * 0.03 + up_table * (0.008 + BALL_RADIUS + 0.001);
+    position.y = ON_FLOOR;
+    let ball = playground.ball(position, Vec3::ZERO);
+
+    // Let the ball settle against the flipper
+    for step in 0..Playground::steps(0.1) {
+        playground.step();
+        assert_on_table(playground.position(ball), "cradle", step);
+    }
+
+    playground.press(Button::LeftFlipper);
+    let lifted = (0..Playground::steps(MAX_TIME)).any(|step| {
+        playground.step();
+        let position = playground.position(ball);
+        assert_on_table(position, "flip", step);
+        position.z < flipper.translation.z - LIFT
+    });
+    assert!(
+        lifted,
+        "cradled ball not lifted by the flipper in {MAX_TIME} s: {}",
+        playground.position(ball)
+    );
+}
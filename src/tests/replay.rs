@@ -0,0 +1,73 @@
+//! Tests of the replays: the same buttons give the same game.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use super::Playground;
+use crate::game::GameState;
+use crate::headless;
+use crate::replay::{Button, Recording};
+use crate::Ball;
+
+/// Time given to the rules to load before the replay starts.
+const LOADING: Duration = Duration::from_secs(10);
+
+/// Positions of the balls at each physics step of the replay of
+/// `recording`, in the coordinates of the table.
+fn replay(recording: Recording, seconds: f32) -> Vec<Vec<Vec3>> {
+    let mut app = App::new();
+    headless::add_simulation(&mut app, recording);
+    app.finish();
+    app.cleanup();
+
+    let started = Instant::now();
+    while *app.world().resource::<State<GameState>>().get() != GameState::Playing {
+        assert!(started.elapsed() < LOADING, "replay not started");
+        app.update();
+    }
+    (0..Playground::steps(seconds))
+        .map(|_| {
+            app.update();
+            let world = app.world_mut();
+            world
+                .query_filtered::<&Transform, With<Ball>>()
+                .iter(world)
+                .map(|transform| transform.translation)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn replays_give_the_same_game() {
+    const SECONDS: f32 = 5.;
+
+    let mut recording = Recording::scripted(
+        7,
+        vec![
+            (0.5, vec![Button::Plunger]),
+            (0.55, Vec::new()),
+            (2., vec![Button::LeftFlipper, Button::RightFlipper]),
+            (2.3, Vec::new()),
+            (3., vec![Button::NudgeLeft]),
+            (3.05, Vec::new()),
+        ],
+    );
+    recording.extend_to(SECONDS.into());
+
+    let first = replay(recording.clone(), SECONDS);
+    let second = replay(recording, SECONDS);
+    assert!(
+        first.iter().any(|balls| !balls.is_empty()),
+        "no ball served"
+    );
+    for (step, (first, second)) in first.iter().zip(&second).enumerate() {
+        assert_eq!(
+            first,
+            second,
+            "replays apart at {:.3} s",
+            Playground::seconds(step)
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Property tests of the mesh generators, checked by the mesh validator,
+//! and of the colliders of the shapes.
+
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use proptest::prelude::*;
+
+use crate::shapes::{
+    validate_mesh, Band, BandError, Ellipse, Flipper, MeshElements, MeshError, Origin, Surface,
+    Table,
+};
+
+fn origin() -> impl Strategy<Value = Origin> {
+    prop_oneof![
+        Just(Origin::Center),
+        Just(Origin::MinXMinZ),
+        Just(Origin::MaxXMinZ),
+        Just(Origin::MinXMaxZ),
+        Just(Origin::MaxXMaxZ),
+    ]
+}
+
+/// Angles between 0 and PI/2, in any order, far enough to make faces.
+fn angles() -> impl Strategy<Value = (f32, f32)> {
+    (0f32..PI / 2. - 0.05)
+        .prop_flat_map(|min| (Just(min), min + 0.05..=PI / 2., any::<bool>()))
+        .prop_map(|(min, max, reverse)| if reverse { (max, min) } else { (min, max) })
+}
+
+/// Check the elements and the Bevy mesh made of them.
+fn validate(elements: MeshElements, surface: Surface) -> Result<(), MeshError> {
+    elements.validate(surface)?;
+    validate_mesh(&elements.into(), surface)
+}
+
+/// Bounds of the vertices of a mesh.
+fn mesh_aabb(elements: &MeshElements) -> (Vec3, Vec3) {
+    elements
+        .vertices
+        .iter()
+        .map(|&vertex| Vec3::from(vertex))
+        .fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), vertex| (min.min(vertex), max.max(vertex)),
+        )
+}
+
+/// Bounds of a collider.
+fn collider_aabb(collider: &Collider) -> (Vec3, Vec3) {
+    let aabb = collider.raw.compute_local_aabb();
+    (aabb.mins.into(), aabb.maxs.into())
+}
+
+/// Flipper of the table, checked against broken copies.
+fn flipper() -> MeshElements {
+    Flipper::new(0.7, 0.05, 0.1, 0.28, 20).into()
+}
+
+proptest! {
+    #[test]
+    fn ellipse_is_valid(
+        rectangle in any::<bool>(),
+        center in origin(),
+        (first_angle, second_angle) in angles(),
+        resolution in 1usize..64,
+        x in 0.05f32..5.,
+        z in 0.05f32..5.,
+        thickness in 0.01f32..1.,
+    ) {
+        let ellipse = Ellipse {
+            rectangle,
+            center,
+            first_angle,
+            second_angle,
+            resolution,
+            x,
+            z,
+            thickness,
+        };
+        // Without its rectangle, it's a band seen from both sides
+        let surface = if rectangle { Surface::Closed } else { Surface::Open };
+        let elements = MeshElements::try_from(ellipse).unwrap();
+        prop_assert_eq!(validate(elements, surface), Ok(()));
+    }
+
+    #[test]
+    fn ellipse_collider_fits_mesh(
+        rectangle in any::<bool>(),
+        center in origin(),
+        (first_angle, second_angle) in angles(),
+        resolution in 1usize..64,
+        collider_resolution in 1usize..64,
+        x in 0.05f32..5.,
+        z in 0.05f32..5.,
+        thickness in 0.01f32..1.,
+    ) {
+        let ellipse = Ellipse {
+            rectangle,
+            center,
+            first_angle,
+            second_angle,
+            resolution,
+            x,
+            z,
+            thickness,
+        };
+        let (collider_min, collider_max) =
+            collider_aabb(&ellipse.collider(collider_resolution).unwrap());
+        let (mesh_min, mesh_max) = mesh_aabb(&MeshElements::try_from(ellipse).unwrap());
+        // The arc ends bound it whatever the resolution, and the walls
+        // without rectangle are thin boxes
+        let tolerance = 0.003;
+        prop_assert!(collider_min.abs_diff_eq(mesh_min, tolerance));
+        prop_assert!(collider_max.abs_diff_eq(mesh_max, tolerance));
+    }
+
+    #[test]
+    fn flipper_is_valid(
+        radius_min in 0.01f32..0.2,
+        radius_difference in 0f32..0.2,
+        length in 0.05f32..3.,
+        thickness in 0.01f32..0.5,
+        resolution in 2usize..64,
+    ) {
+        let radius_max = radius_min + radius_difference;
+        let flipper = Flipper::new(
+            2. * (radius_min + radius_max) + length,
+            radius_min,
+            radius_max,
+            thickness,
+            resolution,
+        );
+        prop_assert_eq!(validate(flipper.into(), Surface::Closed), Ok(()));
+    }
+
+    #[test]
+    fn band_is_valid(
+        posts in 2usize..6,
+        loop_radius in 0.05f32..1.,
+        radii in prop::collection::vec(0.002f32..0.02, 6),
+        thickness in 0.001f32..0.01,
+        height in 0.002f32..0.05,
+        resolution in 2usize..16,
+        reverse in any::<bool>(),
+    ) {
+        // Posts spread on a circle make a convex loop
+        let mut posts: Vec<(Vec2, f32)> = (0..posts)
+            .map(|i| {
+                let angle = i as f32 * TAU / posts as f32;
+                (Vec2::from_angle(angle) * loop_radius, radii[i])
+            })
+            .collect();
+        if reverse {
+            posts.reverse();
+        }
+        let band = Band {
+            posts,
+            thickness,
+            height,
+            resolution,
+        };
+        let elements = MeshElements::try_from(band).unwrap();
+        prop_assert_eq!(validate(elements, Surface::Closed), Ok(()));
+    }
+
+    #[test]
+    fn table_is_valid(
+        height in 0.5f32..20.,
+        width in 0.5f32..20.,
+        wall_height in 0.01f32..2.,
+    ) {
+        let mesh = Table::new(height, width, wall_height).into();
+        // No top, and walls without thickness
+        prop_assert_eq!(validate_mesh(&mesh, Surface::Open), Ok(()));
+    }
+}
+
+#[test]
+fn angles_out_of_range_are_refused() {
+    for (first_angle, second_angle) in [(-0.1, PI / 4.), (0., PI), (PI / 2. + 0.1, 0.)] {
+        let ellipse = Ellipse {
+            first_angle,
+            second_angle,
+            ..default()
+        };
+        assert!(MeshElements::try_from(ellipse).is_err());
+    }
+}
+
+#[test]
+fn ellipse_collider_without_segments_is_refused() {
+    assert!(Ellipse::default().collider(0).is_err());
+}
+
+#[test]
+fn concave_band_is_refused() {
+    // A square with its third corner pushed inside
+    let band = Band {
+        posts: [(0., 0.), (1., 0.), (0.3, 0.3), (0., 1.)]
+            .map(|(x, z)| (Vec2::new(x, z), 0.01))
+            .to_vec(),
+        thickness: 0.003,
+        height: 0.01,
+        resolution: 4,
+    };
+    assert!(matches!(band.collider(), Err(BandError::Concave(_))));
+    assert!(matches!(
+        MeshElements::try_from(band),
+        Err(BandError::Concave(_))
+    ));
+}
+
+#[test]
+fn wrong_winding_is_found() {
+    let mut elements = flipper();
+    elements.indices.swap(1, 2);
+    assert_eq!(elements.validate(Surface::Open), Err(MeshError::Winding(0)));
+}
+
+#[test]
+fn degenerate_triangle_is_found() {
+    let mut elements = flipper();
+    elements.indices[2] = elements.indices[1];
+    assert_eq!(
+        elements.validate(Surface::Open),
+        Err(MeshError::Degenerate(0))
+    );
+}
+
+#[test]
+fn index_out_of_bounds_is_found() {
+    let mut elements = flipper();
+    let vertices = elements.vertices.len();
+    elements.indices[0] = vertices as u32;
+    assert_eq!(
+        elements.validate(Surface::Open),
+        Err(MeshError::IndexOutOfBounds {
+            index: vertices as u32,
+            vertices,
+        })
+    );
+}
+
+#[test]
+fn bad_normal_is_found() {
+    let mut elements = flipper();
+    elements.normals[0] = [0., 2., 0.];
+    assert_eq!(
+        elements.validate(Surface::Open),
+        Err(MeshError::NormalLength {
+            vertex: 0,
+            length: 2.
+        })
+    );
+}
+
+#[test]
+fn hole_is_found() {
+    let mut elements = flipper();
+    elements.indices.truncate(elements.indices.len() - 3);
+    assert_eq!(elements.validate(Surface::Open), Ok(()));
+    assert!(matches!(
+        elements.validate(Surface::Closed),
+        Err(MeshError::Open { faces: 1, .. })
+    ));
+}
@@ -0,0 +1,65 @@
+//! Tests of the warnings and the tilt, ball after ball.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::dmd::Dmd;
+use crate::game::{GameState, NextBall};
+use crate::operator::Settings;
+use crate::replay::Button;
+use crate::tilt::{Tilt, TiltPlugin};
+use crate::PHYSICS_HZ;
+
+/// Tilt rules alone, one physics step an update.
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1. / PHYSICS_HZ,
+        )))
+        .init_state::<GameState>()
+        .init_resource::<Settings>()
+        .init_resource::<Dmd>()
+        .init_resource::<ButtonInput<Button>>()
+        .add_event::<NextBall>()
+        .add_plugins(TiltPlugin);
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app
+}
+
+/// Nudge the table during one physics step.
+fn nudge(app: &mut App) {
+    let mut buttons = app.world_mut().resource_mut::<ButtonInput<Button>>();
+    buttons.press(Button::NudgeLeft);
+    app.update();
+    let mut buttons = app.world_mut().resource_mut::<ButtonInput<Button>>();
+    buttons.release(Button::NudgeLeft);
+    buttons.clear();
+    app.update();
+}
+
+#[test]
+fn warnings_start_again_after_a_tilt() {
+    let mut app = app();
+    let warnings = app.world().resource::<Settings>().tilt_warnings;
+
+    // The first nudge is safe, each of the next ones is a warning
+    for _ in 0..warnings + 2 {
+        nudge(&mut app);
+    }
+    assert!(app.world().resource::<Tilt>().tilted, "table not tilted");
+
+    app.world_mut().send_event(NextBall);
+    app.update();
+    nudge(&mut app);
+    nudge(&mut app);
+    let tilt = app.world().resource::<Tilt>();
+    assert!(!tilt.tilted, "next ball tilted without a warning");
+    assert_eq!(tilt.warnings, 1);
+}
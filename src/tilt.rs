@@ -0,0 +1,108 @@
+//! Nudging the table, with warnings and a tilt when it is shaken
+//! too much.
+//!
+//! Z and / nudge the table to the left and to the right. A tilted
+//! ball loses its flippers, its points and its bonus.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::dmd::{Animation, Dmd};
+use crate::game::{GameState, NextBall};
+use crate::operator::Settings;
+use crate::physics::StepSet;
+use crate::replay::Button;
+use crate::Ball;
+
+/// Impulse in N·s given to the ball by a nudge.
+const NUDGE_IMPULSE: f32 = 0.012;
+/// Nudges closer than this time in seconds give a warning.
+const DANGER_WINDOW: f32 = 1.;
+
+#[derive(Debug, Default, Resource)]
+pub(crate) struct Tilt {
+    pub(crate) warnings: u32,
+    pub(crate) tilted: bool,
+    /// Time of the last nudge
+    last_nudge: Option<f32>,
+}
+
+pub(crate) struct TiltPlugin;
+
+impl Plugin for TiltPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tilt>()
+            .add_systems(OnEnter(GameState::Playing), reset_tilt)
+            .add_systems(
+                FixedUpdate,
+                (nudge.in_set(StepSet::Nudge), end_tilt.in_set(StepSet::Tilt))
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+fn reset_tilt(mut tilt: ResMut<Tilt>) {
+    *tilt = Tilt::default();
+}
+
+fn nudge(
+    mut commands: Commands,
+    buttons: Res<ButtonInput<Button>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut tilt: ResMut<Tilt>,
+    mut dmd: ResMut<Dmd>,
+    balls: Query<Entity, With<Ball>>,
+) {
+    let direction = if buttons.just_pressed(Button::NudgeLeft) {
+        -1.
+    } else if buttons.just_pressed(Button::NudgeRight) {
+        1.
+    } else {
+        return;
+    };
+
+    for ball in &balls {
+        commands.entity(ball).insert(ExternalImpulse {
+            impulse: Vec3::new(direction * NUDGE_IMPULSE, 0., 0.),
+            torque_impulse: Vec3::ZERO,
+        });
+    }
+
+    let now = time.elapsed_seconds();
+    let danger = tilt
+        .last_nudge
+        .is_some_and(|last| now - last < DANGER_WINDOW);
+    tilt.last_nudge = Some(now);
+    if !danger || tilt.tilted {
+        return;
+    }
+
+    tilt.warnings += 1;
+    if tilt.warnings > settings.tilt_warnings {
+        debug!("Tilt");
+        tilt.tilted = true;
+        dmd.stop();
+        dmd.play(Animation::Blink {
+            text: "TILT".to_string(),
+            scale: 2,
+            period: 0.6,
+            duration: 3.,
+        });
+    } else {
+        dmd.play(Animation::Blink {
+            text: "WARNING".to_string(),
+            scale: 2,
+            period: 0.3,
+            duration: 1.,
+        });
+    }
+}
+
+/// The warnings and the tilt end with the ball, so each ball of each
+/// player starts with all its warnings.
+fn end_tilt(mut tilt: ResMut<Tilt>, mut next_ball: EventReader<NextBall>) {
+    if next_ball.read().count() > 0 {
+        *tilt = Tilt::default();
+    }
+}